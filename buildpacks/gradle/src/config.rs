@@ -4,6 +4,22 @@ use libcnb::{Buildpack, Platform};
 
 pub(crate) struct GradleBuildpackConfig {
     pub(crate) gradle_task: Option<String>,
+    /// The path of the Gradle project to build in a multi-project build, i.e. `:app` or
+    /// `:services:api`.
+    pub(crate) gradle_project: Option<String>,
+}
+
+impl GradleBuildpackConfig {
+    /// Qualifies the given task name with the configured Gradle project, i.e. `:app:build`. Task
+    /// names that are already qualified are returned unchanged.
+    pub(crate) fn qualified_task_name(&self, task_name: &str) -> String {
+        match &self.gradle_project {
+            Some(gradle_project) if !task_name.starts_with(':') => {
+                format!("{gradle_project}:{task_name}")
+            }
+            _ => String::from(task_name),
+        }
+    }
 }
 
 impl<T: Buildpack<Platform = GenericPlatform>> From<&BuildContext<T>> for GradleBuildpackConfig {
@@ -14,6 +30,45 @@ impl<T: Buildpack<Platform = GenericPlatform>> From<&BuildContext<T>> for Gradle
                 .env()
                 .get("GRADLE_TASK")
                 .map(|s| s.to_string_lossy().to_string()),
+            gradle_project: context
+                .platform
+                .env()
+                .get("GRADLE_PROJECT")
+                .map(|s| normalize_gradle_project(&s.to_string_lossy()))
+                .filter(|gradle_project| gradle_project != ":"),
+        }
+    }
+}
+
+fn normalize_gradle_project(gradle_project: &str) -> String {
+    format!(":{}", gradle_project.trim().trim_matches(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(gradle_project: Option<&str>) -> GradleBuildpackConfig {
+        GradleBuildpackConfig {
+            gradle_task: None,
+            gradle_project: gradle_project.map(normalize_gradle_project),
         }
     }
+
+    #[test]
+    fn qualified_task_name() {
+        assert_eq!(config(None).qualified_task_name("build"), "build");
+        assert_eq!(
+            config(Some("app")).qualified_task_name("build"),
+            ":app:build"
+        );
+        assert_eq!(
+            config(Some(":services:api")).qualified_task_name("installDist"),
+            ":services:api:installDist"
+        );
+        assert_eq!(
+            config(Some("app")).qualified_task_name(":other:build"),
+            ":other:build"
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::GradleBuildpackError;
 use buildpacks_jvm_shared as shared;
+use buildpacks_jvm_shared::framework::DefaultAppProcessError;
 use indoc::indoc;
 
 #[allow(clippy::too_many_lines, clippy::needless_pass_by_value)]
@@ -38,6 +39,13 @@ pub(crate) fn on_error_gradle_buildpack(error: GradleBuildpackError) {
                 error,
             );
         }
+        GradleBuildpackError::GetProjectDirsError(error) => {
+            shared::log::log_please_try_again_error(
+                "Failed to get Gradle project directories",
+                "Failed to get the project and build directories of the configured Gradle project",
+                error,
+            );
+        }
         GradleBuildpackError::WriteGradlePropertiesError(error) => {
             shared::log::log_please_try_again_error(
                 "Failed to write Gradle configuration",
@@ -73,6 +81,11 @@ pub(crate) fn on_error_gradle_buildpack(error: GradleBuildpackError) {
                 to build your app. Our Dev Center article on preparing a Gradle application for Heroku
                 describes how to create this task:
                 https://devcenter.heroku.com/articles/deploying-gradle-apps-on-heroku
+
+                Without a 'stage' task, applications that use frameworks such as Micronaut, Ktor or
+                Javalin are built with the 'shadowJar' task of the Shadow plugin, the 'buildFatJar'
+                task of the Ktor plugin or the 'installDist' task of the Application plugin. Please
+                apply one of these plugins or add a 'stage' task.
             "},
         ),
         GradleBuildpackError::DetectError(error) => {
@@ -82,12 +95,39 @@ pub(crate) fn on_error_gradle_buildpack(error: GradleBuildpackError) {
                 error,
             );
         }
-        GradleBuildpackError::CannotDetermineDefaultAppProcess(error) => {
+        GradleBuildpackError::CannotDetermineDefaultAppProcess(DefaultAppProcessError::Io(
+            error,
+        )) => {
             shared::log::log_please_try_again_error(
                 "Failed to determine default app process",
                 "Failed to determine default app process",
                 error,
             );
         }
+        GradleBuildpackError::ReadBooleanSettingError(error) => {
+            shared::log::log_read_boolean_setting_error(error);
+        }
+        GradleBuildpackError::SpringBootLayersError(error) => {
+            shared::log::log_spring_boot_layers_error(error);
+        }
+        GradleBuildpackError::CannotReadJavaVersion(error) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not read the version of the installed OpenJDK due to an unexpected I/O error.",
+                error,
+            );
+        }
+        GradleBuildpackError::ProcessDefinitionsError(error) => {
+            shared::log::log_process_definitions_error(error);
+        }
+        GradleBuildpackError::ReadRuntimeImageConfigError(error) => {
+            shared::log::log_runtime_image_config_error(error);
+        }
+        GradleBuildpackError::CannotCreateRuntimeImage(error) => {
+            shared::log::log_runtime_image_error(error);
+        }
+        GradleBuildpackError::NativeImageError(error) => {
+            shared::log::log_native_image_error(error);
+        }
     }
 }
@@ -1,87 +1,93 @@
-use crate::gradle_command::GradleDependencyReport;
-use buildpacks_jvm_shared::fs::list_directory_contents;
-use libcnb::data::launch::{Process, ProcessBuilder};
-use libcnb::data::process_type;
-use std::path::Path;
+use crate::gradle_command::Tasks;
+use buildpacks_jvm_shared::framework::Framework;
 
-pub(crate) fn detect_framework(dependency_report: &GradleDependencyReport) -> Option<Framework> {
-    DEPENDENCY_TO_FRAMEWORK_MAPPINGS.into_iter().find_map(
-        |(configuration, group_id, artifact_id, framework)| {
-            dependency_report
-                .contains_dependency(configuration, group_id, artifact_id)
-                .then_some(framework)
-        },
-    )
+/// The Gradle task that builds a deployable artifact for the given framework.
+///
+/// Fat JAR tasks are only available when the Shadow plugin (`shadowJar`) or the Ktor plugin
+/// (`buildFatJar`) is applied. Without them, the start scripts of the Application plugin
+/// (`installDist`) are used. Returns `None` if the project has none of these tasks.
+pub(crate) fn default_build_task(
+    framework: Framework,
+    project_tasks: &Tasks,
+) -> Option<&'static str> {
+    let candidates: &[&'static str] = match framework {
+        Framework::SpringBoot
+        | Framework::WildflySwarm
+        | Framework::Quarkus
+        | Framework::Helidon => &["build"],
+        Framework::Ratpack => &["installDist"],
+        Framework::Ktor => &["buildFatJar", "installDist"],
+        Framework::Micronaut
+        | Framework::Dropwizard
+        | Framework::Javalin
+        | Framework::Jooby
+        | Framework::Vertx => &["shadowJar", "installDist"],
+    };
+
+    candidates
+        .iter()
+        .copied()
+        .find(|task_name| project_tasks.has_task(task_name))
 }
 
-#[allow(clippy::case_sensitive_file_extension_comparisons)]
-pub(crate) fn default_app_process<P: AsRef<Path>>(
-    dependency_report: &GradleDependencyReport,
-    app_dir: P,
-) -> Result<Option<Process>, std::io::Error> {
-    let jar_path = match detect_framework(dependency_report) {
-        Some(Framework::SpringBoot | Framework::Micronaut) => {
-            list_directory_contents(app_dir.as_ref().join("build/libs"))?.find(|path| {
-                path.file_name()
-                    .map(|file_name| file_name.to_string_lossy().to_string())
-                    .is_some_and(|file_name| {
-                        file_name.ends_with(".jar")
-                        && !file_name.ends_with("-plain.jar") // Spring Boot JAR without dependencies
-                        && !file_name.ends_with("-sources.jar")
-                        && !file_name.ends_with("-javadoc.jar")
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gradle_command::{Task, TaskGroup};
+
+    fn tasks(names: &[&str]) -> Tasks {
+        Tasks {
+            groups: vec![TaskGroup {
+                heading: String::from("Build tasks"),
+                tasks: names
+                    .iter()
+                    .map(|name| Task {
+                        name: String::from(*name),
+                        description: String::new(),
                     })
-            })
+                    .collect(),
+            }],
         }
-        Some(Framework::Quarkus) => {
-            let quarkus_run_jar = app_dir.as_ref().join("build/quarkus-app/quarkus-run.jar");
-            quarkus_run_jar.is_file().then_some(quarkus_run_jar)
-        }
-        _ => None,
-    };
+    }
 
-    let process = jar_path.map(|jar_path| {
-        ProcessBuilder::new(
-            process_type!("web"),
-            ["java", "-jar", &jar_path.to_string_lossy()],
-        )
-        .default(true)
-        .build()
-    });
+    #[test]
+    fn default_build_tasks() {
+        let project_tasks = tasks(&["build", "installDist", "shadowJar", "buildFatJar"]);
 
-    Ok(process)
-}
+        assert_eq!(
+            default_build_task(Framework::SpringBoot, &project_tasks),
+            Some("build")
+        );
+        assert_eq!(
+            default_build_task(Framework::Ratpack, &project_tasks),
+            Some("installDist")
+        );
+        assert_eq!(
+            default_build_task(Framework::Ktor, &project_tasks),
+            Some("buildFatJar")
+        );
+        assert_eq!(
+            default_build_task(Framework::Vertx, &project_tasks),
+            Some("shadowJar")
+        );
+    }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub(crate) enum Framework {
-    Ratpack,
-    SpringBoot,
-    Micronaut,
-    Quarkus,
-}
+    #[test]
+    fn default_build_tasks_without_fat_jar_plugins() {
+        let project_tasks = tasks(&["build", "installDist"]);
+
+        assert_eq!(
+            default_build_task(Framework::Ktor, &project_tasks),
+            Some("installDist")
+        );
+        assert_eq!(
+            default_build_task(Framework::Micronaut, &project_tasks),
+            Some("installDist")
+        );
 
-const DEPENDENCY_TO_FRAMEWORK_MAPPINGS: [(&str, &str, &str, Framework); 4] = [
-    (
-        "runtimeClasspath",
-        "org.springframework.boot",
-        "spring-boot",
-        Framework::SpringBoot,
-    ),
-    (
-        "runtimeClasspath",
-        "io.ratpack",
-        "ratpack-core",
-        Framework::Ratpack,
-    ),
-    (
-        "runtimeClasspath",
-        "io.micronaut",
-        "micronaut-core",
-        Framework::Micronaut,
-    ),
-    (
-        "quarkusProdRuntimeClasspathConfigurationDeployment",
-        "io.quarkus",
-        "quarkus-core",
-        Framework::Quarkus,
-    ),
-];
+        let project_tasks = tasks(&["build"]);
+
+        assert_eq!(default_build_task(Framework::Javalin, &project_tasks), None);
+        assert_eq!(default_build_task(Framework::Ratpack, &project_tasks), None);
+    }
+}
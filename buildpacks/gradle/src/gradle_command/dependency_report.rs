@@ -7,11 +7,12 @@ use std::process::Command;
 pub(crate) fn dependency_report(
     app_dir: &Path,
     env: &Env,
+    dependencies_task_name: &str,
 ) -> Result<GradleDependencyReport, GradleCommandError<()>> {
     let output = Command::new(app_dir.join("gradlew"))
         .current_dir(app_dir)
         .envs(env)
-        .args(["--quiet", "dependencies"])
+        .args(["--quiet", dependencies_task_name])
         .output()
         .map_err(GradleCommandError::Io)?;
 
@@ -37,21 +38,10 @@ pub(crate) struct GradleDependencyReport {
 }
 
 impl GradleDependencyReport {
-    pub(crate) fn contains_dependency(
+    pub(crate) fn flattened_dependencies(
         &self,
         configuration_name: &str,
-        group_id: &str,
-        artifact_id: &str,
-    ) -> bool {
-        self.flattened_dependencies(configuration_name)
-            .unwrap_or_default()
-            .into_iter()
-            .any(|dependency| {
-                dependency.group_id == group_id && dependency.artifact_id == artifact_id
-            })
-    }
-
-    fn flattened_dependencies(&self, configuration_name: &str) -> Option<Vec<Dependency>> {
+    ) -> Option<Vec<Dependency>> {
         self.entries.get(configuration_name).map(|dependencies| {
             let mut acc = vec![];
 
@@ -208,7 +198,7 @@ mod parser {
 
     #[cfg(test)]
     mod test {
-        use super::Suffix::NotResolved;
+        use super::Suffix::{DependenciesOmitted, DependencyConstraint, NotResolved};
         use super::{Dependency, GradleDependencyReport};
         use indoc::indoc;
         use std::collections::BTreeMap;
@@ -632,5 +622,52 @@ mod parser {
                 }
             );
         }
+
+        #[test]
+        fn test_conflict_resolution_and_suffixes() {
+            let result = super::dependency_report(indoc! {r"
+            runtimeClasspath - Runtime classpath of source set 'main'.
+            +--- org.springframework.boot:spring-boot-starter-web -> 3.2.0
+            |    \--- org.yaml:snakeyaml:2.0 -> 2.2 (*)
+            \--- com.fasterxml.jackson.core:jackson-databind:2.15.3 (c)
+            "});
+
+            assert_eq!(
+                result,
+                Ok((
+                    "",
+                    GradleDependencyReport {
+                        entries: BTreeMap::from([(
+                            String::from("runtimeClasspath"),
+                            vec![
+                                Dependency {
+                                    group_id: String::from("org.springframework.boot"),
+                                    artifact_id: String::from("spring-boot-starter-web"),
+                                    package_version: None,
+                                    resolved_package_version: Some(String::from("3.2.0")),
+                                    suffix: None,
+                                    dependencies: vec![Dependency {
+                                        group_id: String::from("org.yaml"),
+                                        artifact_id: String::from("snakeyaml"),
+                                        package_version: Some(String::from("2.0")),
+                                        resolved_package_version: Some(String::from("2.2")),
+                                        suffix: Some(DependenciesOmitted),
+                                        dependencies: vec![]
+                                    }]
+                                },
+                                Dependency {
+                                    group_id: String::from("com.fasterxml.jackson.core"),
+                                    artifact_id: String::from("jackson-databind"),
+                                    package_version: Some(String::from("2.15.3")),
+                                    resolved_package_version: None,
+                                    suffix: Some(DependencyConstraint),
+                                    dependencies: vec![]
+                                }
+                            ]
+                        )])
+                    }
+                ))
+            );
+        }
     }
 }
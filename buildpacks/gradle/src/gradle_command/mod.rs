@@ -1,11 +1,17 @@
 mod daemon;
 mod dependency_report;
+mod properties;
 mod tasks;
 
 pub(crate) use daemon::start as start_daemon;
 pub(crate) use daemon::stop as stop_daemon;
-pub(crate) use dependency_report::{GradleDependencyReport, dependency_report};
-pub(crate) use tasks::tasks;
+#[cfg(test)]
+pub(crate) use dependency_report::Dependency;
+pub(crate) use dependency_report::{GradleDependencyReport, Suffix, dependency_report};
+pub(crate) use properties::{ProjectDirs, project_dirs};
+#[cfg(test)]
+pub(crate) use tasks::{Task, TaskGroup};
+pub(crate) use tasks::{Tasks, tasks};
 
 use std::process::Command;
 
@@ -0,0 +1,81 @@
+use crate::gradle_command::{GradleCommandError, run_gradle_command};
+use libcnb::Env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Queries the project and build directories of a Gradle project with the `properties` task. This
+/// supports projects that configure a custom project or build directory, i.e. in
+/// `settings.gradle`.
+pub(crate) fn project_dirs(
+    app_dir: &Path,
+    env: &Env,
+    properties_task_name: &str,
+) -> Result<ProjectDirs, GradleCommandError<()>> {
+    run_gradle_command(
+        Command::new(app_dir.join("gradlew"))
+            .current_dir(app_dir)
+            .envs(env)
+            .args(["--quiet", properties_task_name]),
+        |stdout, _stderr| parse(stdout).ok_or(()),
+    )
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ProjectDirs {
+    pub(crate) project_dir: PathBuf,
+    pub(crate) build_dir: PathBuf,
+}
+
+fn parse(stdout: &str) -> Option<ProjectDirs> {
+    let property = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+            .map(PathBuf::from)
+    };
+
+    let project_dir = property("projectDir")?;
+
+    Some(ProjectDirs {
+        // Recent Gradle versions no longer list the deprecated buildDir property. The build
+        // directory is then assumed to be in its default location.
+        build_dir: property("buildDir").unwrap_or_else(|| project_dir.join("build")),
+        project_dir,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_properties() {
+        assert_eq!(
+            parse(indoc! {"
+                ------------------------------------------------------------
+                Project ':services:api'
+                ------------------------------------------------------------
+
+                allprojects: [project ':services:api']
+                buildDir: /workspace/out/api
+                projectDir: /workspace/modules/api
+                rootDir: /workspace
+            "}),
+            Some(ProjectDirs {
+                project_dir: PathBuf::from("/workspace/modules/api"),
+                build_dir: PathBuf::from("/workspace/out/api"),
+            })
+        );
+
+        assert_eq!(
+            parse("projectDir: /workspace/app\nrootDir: /workspace\n"),
+            Some(ProjectDirs {
+                project_dir: PathBuf::from("/workspace/app"),
+                build_dir: PathBuf::from("/workspace/app/build"),
+            })
+        );
+
+        assert_eq!(parse("rootDir: /workspace\n"), None);
+    }
+}
@@ -6,12 +6,13 @@ use std::process::Command;
 pub(crate) fn tasks(
     current_dir: &Path,
     env: &Env,
+    tasks_task_name: &str,
 ) -> Result<Tasks, GradleCommandError<nom::error::Error<String>>> {
     run_gradle_command(
         Command::new(current_dir.join("gradlew"))
             .current_dir(current_dir)
             .envs(env)
-            .args(["--quiet", "tasks"]),
+            .args(["--quiet", tasks_task_name]),
         |stdout, _stderr| {
             parser::parse(stdout)
                 .map(|groups| Tasks { groups })
@@ -7,25 +7,55 @@ use crate::GradleBuildpackError::{GradleBuildIoError, GradleBuildUnexpectedStatu
 use crate::config::GradleBuildpackConfig;
 use crate::detect::is_gradle_project_directory;
 use crate::errors::on_error_gradle_buildpack;
-use crate::framework::{Framework, default_app_process, detect_framework};
-use crate::gradle_command::GradleCommandError;
+use crate::framework::default_build_task;
+use crate::gradle_command::{GradleCommandError, ProjectDirs};
 use crate::layers::gradle_home::handle_gradle_home_layer;
+use crate::sbom::runtime_classpath_artifacts;
 use buildpacks_jvm_shared as shared;
+use buildpacks_jvm_shared::cds::read_cds_config;
+use buildpacks_jvm_shared::framework::{
+    BuildOutputDirs, DefaultAppProcessError, Framework, LaunchTarget, default_app_process,
+    detect_framework, find_launch_target,
+};
+use buildpacks_jvm_shared::layers::cds::{ReadJavaVersionError, handle_cds_layer};
+use buildpacks_jvm_shared::layers::class_count::handle_class_count_layer;
+use buildpacks_jvm_shared::layers::framework::handle_framework_layer;
+use buildpacks_jvm_shared::layers::jre::handle_jlink_jre_layer;
+use buildpacks_jvm_shared::layers::native_image::handle_native_image_layer;
+use buildpacks_jvm_shared::layers::spring_boot::handle_spring_boot_layers;
+use buildpacks_jvm_shared::log::{
+    log_cds_unsupported_warning, log_spring_boot_jar_not_layered_warning,
+};
+use buildpacks_jvm_shared::native_image::{NativeImageError, ensure_native_image_available};
 use buildpacks_jvm_shared::output::{
     BuildpackOutputText, BuildpackOutputTextSection, print_buildpack_name, print_section,
     print_subsection, track_buildpack_timing, track_subsection_timing,
 };
+use buildpacks_jvm_shared::processes::{
+    ProcessDefinitionsError, additional_processes, read_process_definitions,
+};
+use buildpacks_jvm_shared::runtime_image::{
+    RuntimeImageConfigError, RuntimeImageError, RuntimeImageType, jdk_requirement,
+    read_runtime_image_config,
+};
+use buildpacks_jvm_shared::sbom::maven_artifacts_cyclonedx_sbom;
+use buildpacks_jvm_shared::spring_boot::{
+    SpringBootLayersError, layered_jar_start_class, read_extract_layers_config,
+};
+use buildpacks_jvm_shared::system_properties::ReadBooleanSettingError;
 #[cfg(test)]
 use buildpacks_jvm_shared_test as _;
 use libcnb::build::{BuildContext, BuildResult, BuildResultBuilder};
 use libcnb::data::build_plan::BuildPlanBuilder;
 use libcnb::data::launch::LaunchBuilder;
+use libcnb::data::process_type;
 use libcnb::detect::{DetectContext, DetectResult, DetectResultBuilder};
 use libcnb::generic::GenericPlatform;
 use libcnb::{Buildpack, Env, Platform, buildpack_main};
 #[cfg(test)]
 use libcnb_test as _;
 use serde::Deserialize;
+use std::path::Path;
 use std::process::{Command, ExitStatus};
 
 mod config;
@@ -34,6 +64,7 @@ mod errors;
 mod framework;
 mod gradle_command;
 mod layers;
+mod sbom;
 
 struct GradleBuildpack;
 
@@ -45,12 +76,20 @@ enum GradleBuildpackError {
     GradleBuildUnexpectedStatusError(ExitStatus),
     GetTasksError(GradleCommandError<()>),
     GetDependencyReportError(GradleCommandError<()>),
+    GetProjectDirsError(GradleCommandError<()>),
     WriteGradlePropertiesError(std::io::Error),
     WriteGradleInitScriptError(std::io::Error),
     CannotSetGradleWrapperExecutableBit(std::io::Error),
-    CannotDetermineDefaultAppProcess(std::io::Error),
+    CannotDetermineDefaultAppProcess(DefaultAppProcessError),
+    ProcessDefinitionsError(ProcessDefinitionsError),
     StartGradleDaemonError(GradleCommandError<()>),
     BuildTaskUnknown,
+    ReadRuntimeImageConfigError(RuntimeImageConfigError),
+    CannotCreateRuntimeImage(RuntimeImageError),
+    NativeImageError(NativeImageError),
+    ReadBooleanSettingError(ReadBooleanSettingError),
+    SpringBootLayersError(SpringBootLayersError),
+    CannotReadJavaVersion(std::io::Error),
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,7 +108,7 @@ impl Buildpack for GradleBuildpack {
             DetectResultBuilder::pass()
                 .build_plan(
                     BuildPlanBuilder::new()
-                        .requires("jdk")
+                        .requires(jdk_requirement())
                         .provides("jvm-application")
                         .requires("jvm-application")
                         .build(),
@@ -80,6 +119,7 @@ impl Buildpack for GradleBuildpack {
         }
     }
 
+    #[allow(clippy::too_many_lines)]
     fn build(&self, context: BuildContext<Self>) -> libcnb::Result<BuildResult, Self::Error> {
         track_buildpack_timing(|| {
             print_buildpack_name("Heroku Gradle Buildpack");
@@ -110,28 +150,75 @@ impl Buildpack for GradleBuildpack {
 
             let project_tasks = track_subsection_timing(|| {
                 print_subsection("Querying tasks");
-                gradle_command::tasks(&context.app_dir, &gradle_env)
-                    .map_err(|command_error| command_error.map_parse_error(|_| ()))
-                    .map_err(GradleBuildpackError::GetTasksError)
+                gradle_command::tasks(
+                    &context.app_dir,
+                    &gradle_env,
+                    &buildpack_config.qualified_task_name("tasks"),
+                )
+                .map_err(|command_error| command_error.map_parse_error(|_| ()))
+                .map_err(GradleBuildpackError::GetTasksError)
             })?;
 
             let dependency_report = track_subsection_timing(|| {
                 print_subsection("Querying dependency report");
-                gradle_command::dependency_report(&context.app_dir, &gradle_env)
-                    .map_err(GradleBuildpackError::GetDependencyReportError)
+                gradle_command::dependency_report(
+                    &context.app_dir,
+                    &gradle_env,
+                    &buildpack_config.qualified_task_name("dependencies"),
+                )
+                .map_err(GradleBuildpackError::GetDependencyReportError)
             })?;
 
+            let ProjectDirs {
+                project_dir,
+                build_dir,
+            } = match &buildpack_config.gradle_project {
+                Some(gradle_project) => track_subsection_timing(|| {
+                    print_subsection("Querying project directories");
+                    gradle_command::project_dirs(
+                        &context.app_dir,
+                        &gradle_env,
+                        &format!("{gradle_project}:properties"),
+                    )
+                    .map_err(GradleBuildpackError::GetProjectDirsError)
+                })?,
+                None => ProjectDirs {
+                    project_dir: context.app_dir.clone(),
+                    build_dir: context.app_dir.join("build"),
+                },
+            };
+
+            let runtime_artifacts = runtime_classpath_artifacts(&dependency_report);
+            let framework = detect_framework(&runtime_artifacts);
+            if let Some(framework) = framework {
+                handle_framework_layer(&context, framework)?;
+            }
+
+            let runtime_image_config =
+                read_runtime_image_config(&context.app_dir, context.platform.env())
+                    .map_err(GradleBuildpackError::ReadRuntimeImageConfigError)?;
+
+            let native = runtime_image_config.image_type == RuntimeImageType::Native;
+            if native {
+                ensure_native_image_available(gradle_env.get("JAVA_HOME").map(Path::new))
+                    .map_err(GradleBuildpackError::NativeImageError)?;
+            }
+
             let task_name = buildpack_config
                 .gradle_task
                 .as_deref()
+                // Provided by the GraalVM Native Build Tools plugin.
+                .or_else(|| native.then_some("nativeCompile"))
                 .or_else(|| project_tasks.has_task("stage").then_some("stage"))
                 .or_else(|| {
-                    detect_framework(&dependency_report).map(|framework| match framework {
-                        Framework::SpringBoot | Framework::Quarkus => "build",
-                        Framework::Ratpack => "installDist",
-                        Framework::Micronaut => "shadowJar",
-                    })
+                    framework.and_then(|framework| default_build_task(framework, &project_tasks))
                 })
+                // Applications without a known framework are expected to build an executable JAR
+                // file with the default lifecycle task.
+                .or_else(|| {
+                    (framework.is_none() && project_tasks.has_task("build")).then_some("build")
+                })
+                .map(|task_name| buildpack_config.qualified_task_name(task_name))
                 .ok_or(GradleBuildpackError::BuildTaskUnknown)?;
 
             print_subsection(BuildpackOutputText::new(vec![
@@ -143,7 +230,7 @@ impl Buildpack for GradleBuildpack {
             build_command
                 .current_dir(&context.app_dir)
                 .envs(&gradle_env)
-                .args([task_name, "-x", "check"]);
+                .args([&task_name, "-x", "check"]);
 
             track_subsection_timing(|| {
                 shared::output::run_command(build_command, false, GradleBuildIoError, |output| {
@@ -155,13 +242,90 @@ impl Buildpack for GradleBuildpack {
             // failure, nor can we recover from it in any way.
             let _ = gradle_command::stop_daemon(&gradle_wrapper_executable_path, &gradle_env);
 
-            let process = default_app_process(&dependency_report, &context.app_dir)
-                .map_err(GradleBuildpackError::CannotDetermineDefaultAppProcess)?;
+            if runtime_image_config.image_type == RuntimeImageType::Jlink {
+                handle_jlink_jre_layer(
+                    &context,
+                    gradle_env.get("JAVA_HOME").map(Path::new),
+                    &build_dir,
+                    &runtime_image_config,
+                )?;
+            }
+
+            let mut launch_target = if native {
+                Some(handle_native_image_layer(
+                    &context,
+                    &build_dir.join("native/nativeCompile"),
+                )?)
+            } else {
+                find_launch_target(
+                    framework,
+                    &BuildOutputDirs {
+                        jars: build_dir.join("libs"),
+                        quarkus_app: build_dir.join("quarkus-app"),
+                        install: Some(build_dir.join("install")),
+                    },
+                )
+                .map_err(GradleBuildpackError::CannotDetermineDefaultAppProcess)?
+            };
 
-            process
-                .map_or(BuildResultBuilder::new(), |process| {
-                    BuildResultBuilder::new().launch(LaunchBuilder::new().process(process).build())
-                })
+            if framework == Some(Framework::SpringBoot)
+                && let Some(LaunchTarget::Jar(jar_path)) = &launch_target
+                && read_extract_layers_config(&context.app_dir, context.platform.env())
+                    .map_err(GradleBuildpackError::ReadBooleanSettingError)?
+            {
+                match layered_jar_start_class(jar_path).map_err(|error| {
+                    GradleBuildpackError::SpringBootLayersError(SpringBootLayersError::Io(error))
+                })? {
+                    Some(start_class) => {
+                        launch_target = Some(handle_spring_boot_layers(
+                            &context,
+                            &gradle_env,
+                            jar_path,
+                            &start_class,
+                        )?);
+                    }
+                    None => log_spring_boot_jar_not_layered_warning(jar_path),
+                }
+            }
+
+            if read_cds_config(&context.app_dir, context.platform.env())
+                .map_err(GradleBuildpackError::ReadBooleanSettingError)?
+            {
+                match &launch_target {
+                    Some(launch_target)
+                        if framework == Some(Framework::SpringBoot)
+                            && runtime_image_config.image_type == RuntimeImageType::Jdk =>
+                    {
+                        handle_cds_layer(&context, &gradle_env, launch_target)?;
+                    }
+                    _ => log_cds_unsupported_warning(),
+                }
+            }
+
+            if let Some(launch_target) = &launch_target {
+                handle_class_count_layer(&context, launch_target)?;
+            }
+
+            let process_definitions = read_process_definitions(&context.app_dir)
+                .map_err(GradleBuildpackError::ProcessDefinitionsError)?;
+
+            let mut launch_builder = LaunchBuilder::new();
+
+            // A `web` process defined in `project.toml` replaces the default process.
+            if !process_definitions.contains_key(&process_type!("web"))
+                && let Some(launch_target) = &launch_target
+            {
+                launch_builder.process(default_app_process(framework, launch_target, &project_dir));
+            }
+
+            launch_builder.processes(
+                additional_processes(&process_definitions, launch_target.as_ref())
+                    .map_err(GradleBuildpackError::ProcessDefinitionsError)?,
+            );
+
+            BuildResultBuilder::new()
+                .launch(launch_builder.build())
+                .launch_sbom(maven_artifacts_cyclonedx_sbom(&runtime_artifacts))
                 .build()
         })
     }
@@ -179,4 +343,28 @@ impl From<GradleBuildpackError> for libcnb::Error<GradleBuildpackError> {
     }
 }
 
+impl From<ReadJavaVersionError> for GradleBuildpackError {
+    fn from(e: ReadJavaVersionError) -> Self {
+        GradleBuildpackError::CannotReadJavaVersion(e.0)
+    }
+}
+
+impl From<NativeImageError> for GradleBuildpackError {
+    fn from(e: NativeImageError) -> Self {
+        GradleBuildpackError::NativeImageError(e)
+    }
+}
+
+impl From<SpringBootLayersError> for GradleBuildpackError {
+    fn from(e: SpringBootLayersError) -> Self {
+        GradleBuildpackError::SpringBootLayersError(e)
+    }
+}
+
+impl From<RuntimeImageError> for GradleBuildpackError {
+    fn from(e: RuntimeImageError) -> Self {
+        GradleBuildpackError::CannotCreateRuntimeImage(e)
+    }
+}
+
 const GRADLE_TASK_NAME_HEROKU_START_DAEMON: &str = "heroku_buildpack_start_daemon";
@@ -0,0 +1,189 @@
+use crate::gradle_command::{GradleDependencyReport, Suffix};
+use buildpacks_jvm_shared::sbom::MavenArtifact;
+
+/// Returns the artifacts of the `runtimeClasspath` configuration, with versions after conflict
+/// resolution.
+pub(crate) fn runtime_classpath_artifacts(
+    dependency_report: &GradleDependencyReport,
+) -> Vec<MavenArtifact> {
+    dependency_report
+        .flattened_dependencies("runtimeClasspath")
+        .unwrap_or_default()
+        .into_iter()
+        // Constraints only influence version resolution and unresolved dependencies are not part
+        // of the classpath. Dependencies with omitted children are listed in full elsewhere, but
+        // are part of the classpath nonetheless.
+        .filter(|dependency| {
+            !matches!(
+                dependency.suffix,
+                Some(Suffix::DependencyConstraint | Suffix::NotResolved)
+            )
+        })
+        .filter_map(|dependency| {
+            dependency
+                .resolved_package_version
+                .or(dependency.package_version)
+                .map(|version| MavenArtifact {
+                    group_id: dependency.group_id,
+                    artifact_id: dependency.artifact_id,
+                    version,
+                    artifact_type: String::from("jar"),
+                    classifier: None,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gradle_command::Dependency;
+    use buildpacks_jvm_shared::framework::{Framework, detect_framework};
+    use std::collections::BTreeMap;
+
+    fn dependency(
+        coordinates: &str,
+        resolved_package_version: Option<&str>,
+        suffix: Option<Suffix>,
+        dependencies: Vec<Dependency>,
+    ) -> Dependency {
+        let mut elements = coordinates.split(':');
+
+        Dependency {
+            group_id: String::from(elements.next().unwrap()),
+            artifact_id: String::from(elements.next().unwrap()),
+            package_version: elements.next().map(String::from),
+            resolved_package_version: resolved_package_version.map(String::from),
+            suffix,
+            dependencies,
+        }
+    }
+
+    #[test]
+    fn runtime_classpath() {
+        let dependency_report = GradleDependencyReport {
+            entries: BTreeMap::from([
+                (
+                    String::from("runtimeClasspath"),
+                    vec![
+                        dependency(
+                            "org.springframework.boot:spring-boot-starter-web",
+                            Some("3.2.0"),
+                            None,
+                            vec![
+                                dependency(
+                                    "org.springframework.boot:spring-boot",
+                                    Some("3.2.0"),
+                                    None,
+                                    vec![],
+                                ),
+                                dependency(
+                                    "org.yaml:snakeyaml:2.0",
+                                    Some("2.2"),
+                                    Some(Suffix::DependenciesOmitted),
+                                    vec![],
+                                ),
+                            ],
+                        ),
+                        dependency(
+                            "com.fasterxml.jackson.core:jackson-databind:2.15.3",
+                            None,
+                            Some(Suffix::DependencyConstraint),
+                            vec![],
+                        ),
+                        dependency("com.google.guava:guava:32.0.0-jre", None, None, vec![]),
+                    ],
+                ),
+                (
+                    String::from("testRuntimeClasspath"),
+                    vec![dependency("junit:junit:4.13.2", None, None, vec![])],
+                ),
+            ]),
+        };
+
+        assert_eq!(
+            runtime_classpath_artifacts(&dependency_report)
+                .iter()
+                .map(MavenArtifact::purl)
+                .collect::<Vec<_>>(),
+            vec![
+                "pkg:maven/org.springframework.boot/spring-boot-starter-web@3.2.0",
+                "pkg:maven/org.springframework.boot/spring-boot@3.2.0",
+                "pkg:maven/org.yaml/snakeyaml@2.2",
+                "pkg:maven/com.google.guava/guava@32.0.0-jre",
+            ]
+        );
+    }
+
+    #[test]
+    fn runtime_classpath_of_quarkus_application() {
+        // Quarkus applications were previously detected from the
+        // `quarkusProdRuntimeClasspathConfigurationDeployment` configuration. The runtime
+        // artifacts of Quarkus extensions depend on `quarkus-core`, so `runtimeClasspath` is
+        // sufficient. The Vert.x dependencies must not cause a Vert.x application to be detected.
+        let dependency_report = GradleDependencyReport {
+            entries: BTreeMap::from([
+                (
+                    String::from("runtimeClasspath"),
+                    vec![
+                        dependency("io.quarkus.platform:quarkus-bom:3.15.1", None, None, vec![]),
+                        dependency(
+                            "io.quarkus:quarkus-rest",
+                            Some("3.15.1"),
+                            None,
+                            vec![dependency(
+                                "io.quarkus:quarkus-vertx-http:3.15.1",
+                                None,
+                                None,
+                                vec![
+                                    dependency(
+                                        "io.quarkus:quarkus-core:3.15.1",
+                                        None,
+                                        None,
+                                        vec![],
+                                    ),
+                                    dependency("io.vertx:vertx-web:4.5.10", None, None, vec![]),
+                                ],
+                            )],
+                        ),
+                        dependency(
+                            "io.quarkus:quarkus-arc",
+                            Some("3.15.1"),
+                            None,
+                            vec![dependency(
+                                "io.quarkus:quarkus-core:3.15.1",
+                                None,
+                                Some(Suffix::DependenciesOmitted),
+                                vec![],
+                            )],
+                        ),
+                    ],
+                ),
+                (
+                    String::from("quarkusProdRuntimeClasspathConfigurationDeployment"),
+                    vec![dependency(
+                        "io.quarkus:quarkus-core-deployment:3.15.1",
+                        None,
+                        None,
+                        vec![],
+                    )],
+                ),
+            ]),
+        };
+
+        assert_eq!(
+            detect_framework(&runtime_classpath_artifacts(&dependency_report)),
+            Some(Framework::Quarkus)
+        );
+    }
+
+    #[test]
+    fn missing_runtime_classpath() {
+        assert_eq!(
+            runtime_classpath_artifacts(&GradleDependencyReport {
+                entries: BTreeMap::new()
+            }),
+            vec![]
+        );
+    }
+}
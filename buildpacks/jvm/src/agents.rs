@@ -0,0 +1,546 @@
+use buildpacks_jvm_shared::result::none_on_not_found;
+use buildpacks_jvm_shared::system_properties::{
+    ReadBooleanSettingError, ReadSystemPropertiesError, read_boolean_setting,
+    read_system_properties,
+};
+use libcnb::Env;
+use libherokubuildpack::inventory::checksum::{Checksum, ChecksumParseError};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::path::Path;
+
+const JAVA_AGENTS_PROPERTY_NAME: &str = "java.agents";
+const JAVA_AGENT_PROPERTY_NAME_PREFIX: &str = "java.agent.";
+const OTEL_JAVA_AGENT_ENABLED_ENV_VAR_NAME: &str = "OTEL_JAVA_AGENT_ENABLED";
+const OTEL_JAVA_AGENT_ENABLED_PROPERTY_NAME: &str = "java.agent.opentelemetry.enabled";
+pub(crate) const OPENTELEMETRY_AGENT_NAME: &str = "opentelemetry";
+
+/// A Java agent that is installed for launch and added to `JAVA_TOOL_OPTIONS` with `-javaagent:`.
+#[derive(Debug, Clone)]
+pub(crate) struct JavaAgent {
+    pub(crate) name: String,
+    pub(crate) url: String,
+    /// The expected checksum of the agent JAR file.
+    pub(crate) checksum: Checksum<Sha256>,
+    /// Options that are passed to the agent, i.e. `-javaagent:agent.jar=<options>`.
+    pub(crate) options: Option<String>,
+}
+
+/// A Java agent that can be installed by name, without configuring its URL. The checksums
+/// published next to the JAR files are not trusted, the checksum of the version is pinned here
+/// instead. Agents without a pinned checksum still require a configured one.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct KnownJavaAgent {
+    pub(crate) name: &'static str,
+    group_id: &'static str,
+    artifact_id: &'static str,
+    version: &'static str,
+    /// The SHA-256 checksum of the JAR file of `version`, as a lowercase hex string.
+    sha256: Option<&'static str>,
+}
+
+impl KnownJavaAgent {
+    /// The URL of the agent JAR file in Maven Central.
+    pub(crate) fn url(&self) -> String {
+        format!(
+            "https://repo1.maven.org/maven2/{group_path}/{artifact_id}/{version}/{artifact_id}-{version}.jar",
+            group_path = self.group_id.replace('.', "/"),
+            artifact_id = self.artifact_id,
+            version = self.version
+        )
+    }
+}
+
+pub(crate) const KNOWN_JAVA_AGENTS: [KnownJavaAgent; 3] = [
+    KnownJavaAgent {
+        name: "elastic-apm",
+        group_id: "co.elastic.apm",
+        artifact_id: "elastic-apm-agent",
+        version: "1.52.0",
+        sha256: None,
+    },
+    KnownJavaAgent {
+        name: "jmx-prometheus",
+        group_id: "io.prometheus.jmx",
+        artifact_id: "jmx_prometheus_javaagent",
+        version: "1.0.1",
+        sha256: None,
+    },
+    KnownJavaAgent {
+        name: OPENTELEMETRY_AGENT_NAME,
+        group_id: "io.opentelemetry.javaagent",
+        artifact_id: "opentelemetry-javaagent",
+        version: "2.10.0",
+        sha256: None,
+    },
+];
+
+/// Reads the Java agents configured in the app directory.
+///
+/// Agents can be configured in the `[[com.heroku.buildpacks.jvm.agents]]` array of tables in
+/// `project.toml`:
+///
+/// ```toml
+/// [[com.heroku.buildpacks.jvm.agents]]
+/// name = "elastic-apm"
+/// sha256 = "<SHA-256 checksum of elastic-apm-agent-1.52.0.jar>"
+///
+/// [[com.heroku.buildpacks.jvm.agents]]
+/// name = "custom"
+/// url = "https://example.com/custom-agent.jar"
+/// sha256 = "7cafe7cc263ba63115fb801049efb9bfae762c46f252a451ba05734224cb42da"
+/// options = "key=value"
+/// ```
+///
+/// Alternatively, the agent names can be listed in the `java.agents` property in
+/// `system.properties`. URL, checksum and options of an agent are configured with the
+/// `java.agent.<name>.url`, `java.agent.<name>.sha256` and `java.agent.<name>.options` properties.
+///
+/// Agents without a URL must be one of the [`KNOWN_JAVA_AGENTS`], which use their pinned checksum.
+/// Agents with a URL, and known agents without a pinned checksum, require a configured checksum.
+///
+/// The OpenTelemetry agent can also be enabled with the `OTEL_JAVA_AGENT_ENABLED` environment
+/// variable or the `java.agent.opentelemetry.enabled` property in `system.properties`. The
+/// environment variable takes precedence.
+pub(crate) fn read_java_agents(
+    app_dir: &Path,
+    env: &Env,
+) -> Result<Vec<JavaAgent>, JavaAgentsConfigError> {
+    let properties =
+        read_system_properties(app_dir).map_err(JavaAgentsConfigError::ReadSystemProperties)?;
+
+    let mut definitions = none_on_not_found(std::fs::read_to_string(app_dir.join("project.toml")))
+        .map_err(JavaAgentsConfigError::ReadProjectToml)?
+        .map(|project_toml| parse_project_toml_agent_definitions(&project_toml))
+        .transpose()?
+        .unwrap_or_default();
+
+    definitions.extend(system_properties_agent_definitions(&properties));
+
+    let opentelemetry_agent_enabled = read_boolean_setting(
+        app_dir,
+        env,
+        OTEL_JAVA_AGENT_ENABLED_ENV_VAR_NAME,
+        OTEL_JAVA_AGENT_ENABLED_PROPERTY_NAME,
+    )
+    .map_err(JavaAgentsConfigError::ReadOpenTelemetryAgentEnabled)?
+    .unwrap_or_default();
+
+    // An explicitly configured OpenTelemetry agent (i.e. with a custom URL) takes precedence.
+    if opentelemetry_agent_enabled
+        && !definitions
+            .iter()
+            .any(|definition| definition.name == OPENTELEMETRY_AGENT_NAME)
+    {
+        definitions.push(system_properties_agent_definition(
+            &properties,
+            OPENTELEMETRY_AGENT_NAME,
+        ));
+    }
+
+    let mut agents: Vec<JavaAgent> = Vec::new();
+    for definition in definitions {
+        let agent = java_agent(definition, &KNOWN_JAVA_AGENTS)?;
+
+        if agents.iter().any(|other| other.name == agent.name) {
+            return Err(JavaAgentsConfigError::DuplicateAgent(agent.name));
+        }
+
+        agents.push(agent);
+    }
+
+    Ok(agents)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JavaAgentDefinition {
+    name: String,
+    url: Option<String>,
+    sha256: Option<String>,
+    options: Option<String>,
+}
+
+fn parse_project_toml_agent_definitions(
+    project_toml: &str,
+) -> Result<Vec<JavaAgentDefinition>, JavaAgentsConfigError> {
+    let project_toml = toml::from_str::<toml::Table>(project_toml)
+        .map_err(JavaAgentsConfigError::ParseProjectToml)?;
+
+    ["com", "heroku", "buildpacks", "jvm"]
+        .iter()
+        .try_fold(&project_toml, |table, key| {
+            table.get(*key).and_then(toml::Value::as_table)
+        })
+        .and_then(|table| table.get("agents"))
+        .cloned()
+        .map(|agents| {
+            agents
+                .try_into::<Vec<JavaAgentDefinition>>()
+                .map_err(JavaAgentsConfigError::ParseProjectToml)
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+fn system_properties_agent_definitions(
+    properties: &HashMap<String, String>,
+) -> Vec<JavaAgentDefinition> {
+    properties
+        .get(JAVA_AGENTS_PROPERTY_NAME)
+        .map(|names| {
+            names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| system_properties_agent_definition(properties, name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn system_properties_agent_definition(
+    properties: &HashMap<String, String>,
+    name: &str,
+) -> JavaAgentDefinition {
+    let agent_property = |key: &str| {
+        properties
+            .get(&format!("{JAVA_AGENT_PROPERTY_NAME_PREFIX}{name}.{key}"))
+            .cloned()
+    };
+
+    JavaAgentDefinition {
+        name: String::from(name),
+        url: agent_property("url"),
+        sha256: agent_property("sha256"),
+        options: agent_property("options"),
+    }
+}
+
+fn java_agent(
+    definition: JavaAgentDefinition,
+    known_agents: &[KnownJavaAgent],
+) -> Result<JavaAgent, JavaAgentsConfigError> {
+    let JavaAgentDefinition {
+        name,
+        url,
+        sha256,
+        options,
+    } = definition;
+
+    // The name is used as part of the layer name.
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '-')
+    {
+        return Err(JavaAgentsConfigError::InvalidName(name));
+    }
+
+    let options = options
+        .map(|options| String::from(options.trim()))
+        .filter(|options| !options.is_empty());
+
+    let known_agent = known_agents
+        .iter()
+        .find(|known_agent| known_agent.name == name);
+
+    // A configured checksum is only used for agents from a configured URL: the pinned checksum of
+    // a known agent always matches the version the buildpack downloads.
+    let (url, sha256) = match (url, known_agent) {
+        (Some(url), _) => (String::from(url.trim()), sha256),
+        (None, Some(known_agent)) => (
+            known_agent.url(),
+            known_agent.sha256.map(String::from).or(sha256),
+        ),
+        (None, None) if sha256.is_some() => return Err(JavaAgentsConfigError::MissingUrl(name)),
+        (None, None) => return Err(JavaAgentsConfigError::UnknownAgent(name)),
+    };
+
+    let Some(sha256) = sha256 else {
+        return Err(JavaAgentsConfigError::MissingChecksum(name));
+    };
+
+    format!("sha256:{}", sha256.trim().to_lowercase())
+        .parse::<Checksum<Sha256>>()
+        .map_err(|error| JavaAgentsConfigError::InvalidChecksum(name.clone(), error))
+        .map(|checksum| JavaAgent {
+            name,
+            url,
+            checksum,
+            options,
+        })
+}
+
+#[derive(Debug)]
+pub(crate) enum JavaAgentsConfigError {
+    ReadSystemProperties(ReadSystemPropertiesError),
+    ReadOpenTelemetryAgentEnabled(ReadBooleanSettingError),
+    ReadProjectToml(std::io::Error),
+    ParseProjectToml(toml::de::Error),
+    InvalidName(String),
+    UnknownAgent(String),
+    MissingChecksum(String),
+    MissingUrl(String),
+    InvalidChecksum(String, ChecksumParseError),
+    DuplicateAgent(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SHA256: &str = "7cafe7cc263ba63115fb801049efb9bfae762c46f252a451ba05734224cb42da";
+
+    #[test]
+    fn no_agents() {
+        let app_dir = tempdir().unwrap();
+        assert!(
+            read_java_agents(app_dir.path(), &Env::new())
+                .unwrap()
+                .is_empty()
+        );
+
+        std::fs::write(
+            app_dir.path().join("project.toml"),
+            "[com.heroku.buildpacks.jvm.processes.worker]\nargs = [\"worker\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.version=21\n",
+        )
+        .unwrap();
+
+        assert!(
+            read_java_agents(app_dir.path(), &Env::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn agents_from_project_toml_and_system_properties() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("project.toml"),
+            format!(
+                r#"
+                [[com.heroku.buildpacks.jvm.agents]]
+                name = "custom"
+                url = "https://example.com/custom-agent.jar"
+                sha256 = "{}"
+                options = "key=value"
+                "#,
+                SHA256.to_uppercase()
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            format!(
+                "java.agents=elastic-apm, jmx-prometheus\njava.agent.elastic-apm.sha256={SHA256}\njava.agent.jmx-prometheus.sha256={SHA256}\njava.agent.jmx-prometheus.options=9404:config.yaml\n"
+            ),
+        )
+        .unwrap();
+
+        let agents = read_java_agents(app_dir.path(), &Env::new()).unwrap();
+
+        assert_eq!(
+            agents
+                .iter()
+                .map(|agent| agent.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["custom", "elastic-apm", "jmx-prometheus"]
+        );
+
+        assert_eq!(agents[0].url, "https://example.com/custom-agent.jar");
+        assert_eq!(hex::encode(&agents[0].checksum.value), SHA256);
+        assert_eq!(agents[0].options, Some(String::from("key=value")));
+
+        assert_eq!(
+            agents[1].url,
+            "https://repo1.maven.org/maven2/co/elastic/apm/elastic-apm-agent/1.52.0/elastic-apm-agent-1.52.0.jar"
+        );
+        assert_eq!(hex::encode(&agents[1].checksum.value), SHA256);
+        assert_eq!(agents[1].options, None);
+
+        assert_eq!(agents[2].options, Some(String::from("9404:config.yaml")));
+    }
+
+    #[test]
+    fn invalid_agent_configuration() {
+        let definition = |name: &str, url: Option<&str>, sha256: Option<&str>| {
+            java_agent(
+                JavaAgentDefinition {
+                    name: String::from(name),
+                    url: url.map(String::from),
+                    sha256: sha256.map(String::from),
+                    options: None,
+                },
+                &KNOWN_JAVA_AGENTS,
+            )
+        };
+
+        assert!(matches!(
+            definition(
+                "Custom Agent",
+                Some("https://example.com/agent.jar"),
+                Some(SHA256)
+            ),
+            Err(JavaAgentsConfigError::InvalidName(_))
+        ));
+        assert!(matches!(
+            definition("unknown", None, None),
+            Err(JavaAgentsConfigError::UnknownAgent(_))
+        ));
+        assert!(matches!(
+            definition("elastic-apm", None, None),
+            Err(JavaAgentsConfigError::MissingChecksum(_))
+        ));
+        assert!(matches!(
+            definition("custom", Some("https://example.com/agent.jar"), None),
+            Err(JavaAgentsConfigError::MissingChecksum(_))
+        ));
+        assert!(matches!(
+            definition("custom", None, Some(SHA256)),
+            Err(JavaAgentsConfigError::MissingUrl(_))
+        ));
+        assert!(matches!(
+            definition("custom", Some("https://example.com/agent.jar"), Some("abc")),
+            Err(JavaAgentsConfigError::InvalidChecksum(_, _))
+        ));
+    }
+
+    #[test]
+    fn pinned_checksums_of_known_agents() {
+        const PINNED_SHA256: &str =
+            "0000000000000000000000000000000000000000000000000000000000000000";
+
+        let known_agents = [KnownJavaAgent {
+            name: "pinned",
+            group_id: "com.example",
+            artifact_id: "pinned-agent",
+            version: "1.0.0",
+            sha256: Some(PINNED_SHA256),
+        }];
+
+        let definition = |url: Option<&str>, sha256: Option<&str>| {
+            java_agent(
+                JavaAgentDefinition {
+                    name: String::from("pinned"),
+                    url: url.map(String::from),
+                    sha256: sha256.map(String::from),
+                    options: None,
+                },
+                &known_agents,
+            )
+            .map(|agent| (agent.url, hex::encode(&agent.checksum.value)))
+            .unwrap()
+        };
+
+        let maven_central_url = String::from(
+            "https://repo1.maven.org/maven2/com/example/pinned-agent/1.0.0/pinned-agent-1.0.0.jar",
+        );
+
+        assert_eq!(
+            definition(None, None),
+            (maven_central_url.clone(), String::from(PINNED_SHA256))
+        );
+        assert_eq!(
+            definition(None, Some(SHA256)),
+            (maven_central_url, String::from(PINNED_SHA256))
+        );
+        assert_eq!(
+            definition(Some("https://example.com/agent.jar"), Some(SHA256)),
+            (
+                String::from("https://example.com/agent.jar"),
+                String::from(SHA256)
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_and_unknown_fields() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("project.toml"),
+            format!("[[com.heroku.buildpacks.jvm.agents]]\nname = \"elastic-apm\"\nsha256 = \"{SHA256}\"\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            format!("java.agents=elastic-apm\njava.agent.elastic-apm.sha256={SHA256}\n"),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_java_agents(app_dir.path(), &Env::new()),
+            Err(JavaAgentsConfigError::DuplicateAgent(name)) if name == "elastic-apm"
+        ));
+
+        std::fs::write(
+            app_dir.path().join("project.toml"),
+            "[[com.heroku.buildpacks.jvm.agents]]\nname = \"elastic-apm\"\nversion = \"1.0\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_java_agents(app_dir.path(), &Env::new()),
+            Err(JavaAgentsConfigError::ParseProjectToml(_))
+        ));
+    }
+
+    #[test]
+    fn opentelemetry_agent_opt_in() {
+        let app_dir = tempdir().unwrap();
+        let agent_names = |env: &Env| {
+            read_java_agents(app_dir.path(), env)
+                .unwrap()
+                .into_iter()
+                .map(|agent| agent.name)
+                .collect::<Vec<_>>()
+        };
+
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            format!(
+                "java.agents=elastic-apm\njava.agent.elastic-apm.sha256={SHA256}\njava.agent.opentelemetry.enabled=true\njava.agent.opentelemetry.sha256={SHA256}\n"
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            agent_names(&Env::new()),
+            vec!["elastic-apm", "opentelemetry"]
+        );
+
+        let mut env = Env::new();
+        env.insert(OTEL_JAVA_AGENT_ENABLED_ENV_VAR_NAME, "false");
+        assert_eq!(agent_names(&env), vec!["elastic-apm"]);
+
+        env.insert(OTEL_JAVA_AGENT_ENABLED_ENV_VAR_NAME, "yes");
+        assert!(matches!(
+            read_java_agents(app_dir.path(), &env),
+            Err(JavaAgentsConfigError::ReadOpenTelemetryAgentEnabled(
+                ReadBooleanSettingError::InvalidValue { .. }
+            ))
+        ));
+
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            format!(
+                "java.agents=opentelemetry\njava.agent.opentelemetry.url=https://example.com/otel.jar\njava.agent.opentelemetry.sha256={SHA256}\n"
+            ),
+        )
+        .unwrap();
+
+        env.insert(OTEL_JAVA_AGENT_ENABLED_ENV_VAR_NAME, "true");
+        let agents = read_java_agents(app_dir.path(), &env).unwrap();
+
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].url, "https://example.com/otel.jar");
+    }
+}
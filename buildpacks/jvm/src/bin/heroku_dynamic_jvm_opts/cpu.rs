@@ -0,0 +1,234 @@
+use std::path::Path;
+
+/// Reads the CPU quota of the container from the cgroup v2 (`cpu.max`) or cgroup v1
+/// (`cpu.cfs_quota_us` and `cpu.cfs_period_us`) files in `cgroup_dir` and returns the number of
+/// processors it corresponds to, rounded up. A container without a quota is reported as `None`.
+pub(crate) fn detect_cpu_limit(cgroup_dir: &Path) -> Option<u32> {
+    let (quota, period) = read_cgroup_v2_cpu_max(cgroup_dir)
+        .or_else(|| read_cgroup_v1_cpu_quota(&cgroup_dir.join("cpu")))
+        .or_else(|| read_cgroup_v1_cpu_quota(&cgroup_dir.join("cpu,cpuacct")))?;
+
+    (quota > 0 && period > 0)
+        .then(|| quota.div_ceil(period))
+        .and_then(|processors| u32::try_from(processors).ok())
+}
+
+/// The CPU related JVM options for the given number of processors. Options that are already set
+/// in `user_jvm_options` are omitted. A garbage collector is only selected when it differs from
+/// the one the JVM would select itself. Users can override it by setting any `-XX:+Use*GC` option,
+/// such as `-XX:+UseZGC`, in `JAVA_TOOL_OPTIONS`, `JDK_JAVA_OPTIONS` or `JAVA_OPTS`.
+pub(crate) fn cpu_jvm_options(
+    processors: u32,
+    total_memory: Option<u64>,
+    user_jvm_options: &str,
+) -> Vec<String> {
+    let user_options = user_jvm_options.split_whitespace().collect::<Vec<_>>();
+    let is_user_option =
+        |prefix: &str| user_options.iter().any(|option| option.starts_with(prefix));
+
+    let mut options = Vec::new();
+
+    if !is_user_option("-XX:ActiveProcessorCount=") {
+        options.push(format!("-XX:ActiveProcessorCount={processors}"));
+    }
+
+    let garbage_collector = garbage_collector_option(processors, total_memory);
+    if garbage_collector != ergonomic_garbage_collector_option(processors, total_memory)
+        && !GC_OPTIONS.iter().any(|option| is_user_option(option))
+    {
+        options.push(String::from(garbage_collector));
+    }
+
+    if !is_user_option("-XX:CICompilerCount=") {
+        options.push(format!(
+            "-XX:CICompilerCount={}",
+            compiler_count(processors)
+        ));
+    }
+
+    options
+}
+
+/// Selects a garbage collector. The serial collector has the least overhead on a single processor.
+/// With more processors, the parallel collector is used for small heaps and G1 otherwise.
+fn garbage_collector_option(processors: u32, total_memory: Option<u64>) -> &'static str {
+    if processors < 2 {
+        "-XX:+UseSerialGC"
+    } else if total_memory.is_some_and(|total_memory| total_memory < PARALLEL_GC_MEMORY_LIMIT) {
+        "-XX:+UseParallelGC"
+    } else {
+        "-XX:+UseG1GC"
+    }
+}
+
+/// The garbage collector the JVM selects on its own: G1 on server class machines, with at least two
+/// processors and 1792 MiB of memory, and the serial collector otherwise.
+fn ergonomic_garbage_collector_option(processors: u32, total_memory: Option<u64>) -> &'static str {
+    if processors >= 2
+        && total_memory.is_none_or(|total_memory| total_memory >= SERVER_CLASS_MEMORY_LIMIT)
+    {
+        "-XX:+UseG1GC"
+    } else {
+        "-XX:+UseSerialGC"
+    }
+}
+
+/// The number of JIT compiler threads, calculated like the JVM does for tiered compilation. At
+/// least two threads are needed, one for each compiler.
+fn compiler_count(processors: u32) -> u32 {
+    let log_processors = processors.max(1).ilog2();
+    let log_log_processors = log_processors.max(1).ilog2();
+
+    (log_processors * log_log_processors * 3 / 2).max(2)
+}
+
+fn read_cgroup_v2_cpu_max(cgroup_dir: &Path) -> Option<(u64, u64)> {
+    let contents = std::fs::read_to_string(cgroup_dir.join("cpu.max")).ok()?;
+    let mut values = contents.split_whitespace();
+
+    // An unlimited quota is written as "max", which fails to parse.
+    let quota = values.next()?.parse().ok()?;
+    let period = values
+        .next()
+        .map_or(Some(CGROUP_DEFAULT_CPU_PERIOD), |period| {
+            period.parse().ok()
+        })?;
+
+    Some((quota, period))
+}
+
+fn read_cgroup_v1_cpu_quota(cpu_dir: &Path) -> Option<(u64, u64)> {
+    let read_value = |file_name: &str| {
+        std::fs::read_to_string(cpu_dir.join(file_name))
+            .ok()
+            // An unlimited quota is written as -1, which fails to parse as an unsigned number.
+            .and_then(|contents| contents.trim().parse::<u64>().ok())
+    };
+
+    Some((
+        read_value("cpu.cfs_quota_us")?,
+        read_value("cpu.cfs_period_us")?,
+    ))
+}
+
+const CGROUP_DEFAULT_CPU_PERIOD: u64 = 100_000;
+const PARALLEL_GC_MEMORY_LIMIT: u64 = 2 * 1024 * 1024 * 1024;
+const SERVER_CLASS_MEMORY_LIMIT: u64 = 1792 * 1024 * 1024;
+const GC_OPTIONS: [&str; 7] = [
+    "-XX:+UseSerialGC",
+    "-XX:+UseParallelGC",
+    "-XX:+UseG1GC",
+    "-XX:+UseZGC",
+    "-XX:+UseShenandoahGC",
+    "-XX:+UseConcMarkSweepGC",
+    "-XX:+UseEpsilonGC",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn cpu_limit_from_cgroup_v2() {
+        let cgroup_dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect_cpu_limit(cgroup_dir.path()), None);
+
+        fs::write(cgroup_dir.path().join("cpu.max"), "max 100000\n").unwrap();
+        assert_eq!(detect_cpu_limit(cgroup_dir.path()), None);
+
+        fs::write(cgroup_dir.path().join("cpu.max"), "200000 100000\n").unwrap();
+        assert_eq!(detect_cpu_limit(cgroup_dir.path()), Some(2));
+
+        fs::write(cgroup_dir.path().join("cpu.max"), "150000 100000\n").unwrap();
+        assert_eq!(detect_cpu_limit(cgroup_dir.path()), Some(2));
+
+        fs::write(cgroup_dir.path().join("cpu.max"), "50000 100000\n").unwrap();
+        assert_eq!(detect_cpu_limit(cgroup_dir.path()), Some(1));
+    }
+
+    #[test]
+    fn cpu_limit_from_cgroup_v1() {
+        for cpu_dir_name in ["cpu", "cpu,cpuacct"] {
+            let cgroup_dir = tempfile::tempdir().unwrap();
+            let cpu_dir = cgroup_dir.path().join(cpu_dir_name);
+            fs::create_dir(&cpu_dir).unwrap();
+
+            fs::write(cpu_dir.join("cpu.cfs_quota_us"), "-1\n").unwrap();
+            fs::write(cpu_dir.join("cpu.cfs_period_us"), "100000\n").unwrap();
+            assert_eq!(detect_cpu_limit(cgroup_dir.path()), None);
+
+            fs::write(cpu_dir.join("cpu.cfs_quota_us"), "400000\n").unwrap();
+            assert_eq!(detect_cpu_limit(cgroup_dir.path()), Some(4));
+        }
+    }
+
+    #[test]
+    fn garbage_collector_selection() {
+        assert_eq!(
+            garbage_collector_option(1, Some(4 * 1024 * 1024 * 1024)),
+            "-XX:+UseSerialGC"
+        );
+        assert_eq!(
+            garbage_collector_option(2, Some(1024 * 1024 * 1024)),
+            "-XX:+UseParallelGC"
+        );
+        assert_eq!(
+            garbage_collector_option(2, Some(2 * 1024 * 1024 * 1024)),
+            "-XX:+UseG1GC"
+        );
+        assert_eq!(garbage_collector_option(8, None), "-XX:+UseG1GC");
+    }
+
+    #[test]
+    fn ergonomic_garbage_collector_selection() {
+        assert_eq!(
+            ergonomic_garbage_collector_option(1, None),
+            "-XX:+UseSerialGC"
+        );
+        assert_eq!(
+            ergonomic_garbage_collector_option(2, Some(1024 * 1024 * 1024)),
+            "-XX:+UseSerialGC"
+        );
+        assert_eq!(
+            ergonomic_garbage_collector_option(2, Some(1792 * 1024 * 1024)),
+            "-XX:+UseG1GC"
+        );
+        assert_eq!(ergonomic_garbage_collector_option(8, None), "-XX:+UseG1GC");
+    }
+
+    #[test]
+    fn compiler_counts() {
+        assert_eq!(compiler_count(1), 2);
+        assert_eq!(compiler_count(2), 2);
+        assert_eq!(compiler_count(4), 3);
+        assert_eq!(compiler_count(8), 4);
+        assert_eq!(compiler_count(16), 12);
+    }
+
+    #[test]
+    fn user_options_are_kept() {
+        assert_eq!(
+            cpu_jvm_options(4, Some(1024 * 1024 * 1024), ""),
+            vec![
+                "-XX:ActiveProcessorCount=4",
+                "-XX:+UseParallelGC",
+                "-XX:CICompilerCount=3"
+            ]
+        );
+        assert_eq!(
+            cpu_jvm_options(4, None, ""),
+            vec!["-XX:ActiveProcessorCount=4", "-XX:CICompilerCount=3"]
+        );
+        for user_options in [
+            "-XX:+UseZGC -XX:CICompilerCount=6",
+            "-XX:+UseEpsilonGC -XX:CICompilerCount=6",
+            "-XX:+UseConcMarkSweepGC -XX:CICompilerCount=6",
+        ] {
+            assert_eq!(
+                cpu_jvm_options(4, Some(1024 * 1024 * 1024), user_options),
+                vec!["-XX:ActiveProcessorCount=4"]
+            );
+        }
+    }
+}
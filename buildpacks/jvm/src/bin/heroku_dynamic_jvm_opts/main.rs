@@ -0,0 +1,353 @@
+// Required due to: https://github.com/rust-lang/rust/issues/95513
+#![allow(unused_crate_dependencies)]
+
+mod cpu;
+mod memory;
+
+use crate::cpu::{cpu_jvm_options, detect_cpu_limit};
+use crate::memory::{
+    JvmMemoryOptions, MemoryCalculatorConfig, MemoryCalculatorError, calculate_jvm_memory_options,
+    detect_memory_limit, estimate_loaded_class_count,
+};
+use buildpacks_jvm_shared::class_count::{
+    APPLICATION_CLASS_COUNT_ENV_VAR_NAME, directory_class_count,
+};
+use libcnb::Env;
+use libcnb::data::exec_d::ExecDProgramOutputKey;
+use libcnb::data::exec_d_program_output_key;
+use libcnb::exec_d::write_exec_d_program_output;
+use std::collections::HashMap;
+use std::path::Path;
+
+fn main() {
+    write_exec_d_program_output(output_from_env(
+        &Env::from_current(),
+        Path::new(SYSFS_ROOT),
+        // exec.d programs are run in the application directory.
+        Path::new("."),
+    ));
+}
+
+fn output_from_env(
+    env: &Env,
+    sysfs_root: &Path,
+    app_dir: &Path,
+) -> HashMap<ExecDProgramOutputKey, String> {
+    let cgroup_dir = sysfs_root.join("fs/cgroup");
+    let total_memory = detect_memory_limit(&cgroup_dir);
+    let user_jvm_options = user_jvm_options(env);
+
+    let mut jvm_opts = dyno_jvm_opts(env, total_memory, &user_jvm_options)
+        .unwrap_or_else(|| memory_jvm_opts(env, total_memory, &user_jvm_options, app_dir));
+
+    if let Some(processors) = detect_cpu_limit(&cgroup_dir) {
+        // Options that are already set, such as the compiler count of dynos, are not set again.
+        let configured_jvm_options = format!("{} {user_jvm_options}", jvm_opts.join(" "));
+        jvm_opts.extend(cpu_jvm_options(
+            processors,
+            total_memory,
+            &configured_jvm_options,
+        ));
+    }
+
+    let prefix = jvm_opts.join(" ");
+
+    let suffix = env
+        .get("JAVA_TOOL_OPTIONS")
+        .map(|value| format!(" {}", value.to_string_lossy()))
+        .unwrap_or_default();
+
+    HashMap::from([(
+        exec_d_program_output_key!("JAVA_TOOL_OPTIONS"),
+        format!("{prefix}{suffix}"),
+    )])
+}
+
+// The options for the memory limits of Heroku dynos. These are used unless the memory calculator is
+// configured or the user sizes the heap relative to the memory limit.
+fn dyno_jvm_opts(
+    env: &Env,
+    total_memory: Option<u64>,
+    user_jvm_options: &str,
+) -> Option<Vec<String>> {
+    let memory_calculator_configured = [
+        HEADROOM_ENV_VAR_NAME,
+        THREAD_COUNT_ENV_VAR_NAME,
+        LOADED_CLASS_COUNT_ENV_VAR_NAME,
+    ]
+    .into_iter()
+    .any(|name| env.contains_key(name));
+
+    if memory_calculator_configured || JvmMemoryOptions::parse(user_jvm_options).heap_ram_percentage
+    {
+        return None;
+    }
+
+    let jvm_opts: &[&str] = match total_memory? {
+        // Eco, Basic, Standard-1X
+        536_870_912 => &["-Xmx300m", "-Xss512k", "-XX:CICompilerCount=2"],
+        // Standard-2X, Private-S
+        1_073_741_824 => &["-Xmx671m", "-XX:CICompilerCount=2"],
+        _ => return None,
+    };
+
+    Some(jvm_opts.iter().copied().map(String::from).collect())
+}
+
+fn memory_jvm_opts(
+    env: &Env,
+    total_memory: Option<u64>,
+    user_jvm_options: &str,
+    app_dir: &Path,
+) -> Vec<String> {
+    let Some(total_memory) = total_memory else {
+        return ergonomics_jvm_opts();
+    };
+
+    let config = MemoryCalculatorConfig {
+        headroom_percentage: env_var_number(env, HEADROOM_ENV_VAR_NAME)
+            .unwrap_or(DEFAULT_HEADROOM_PERCENTAGE),
+        thread_count: env_var_number(env, THREAD_COUNT_ENV_VAR_NAME)
+            .unwrap_or(DEFAULT_THREAD_COUNT),
+        loaded_class_count: env_var_number(env, LOADED_CLASS_COUNT_ENV_VAR_NAME).unwrap_or_else(
+            || {
+                // The class count is calculated during the build. Applications that were not built
+                // by a buildpack that does that are counted at launch instead.
+                estimate_loaded_class_count(
+                    env_var_number(env, APPLICATION_CLASS_COUNT_ENV_VAR_NAME)
+                        .unwrap_or_else(|| directory_class_count(app_dir)),
+                )
+            },
+        ),
+        user_options: JvmMemoryOptions::parse(user_jvm_options),
+    };
+
+    match calculate_jvm_memory_options(total_memory, &config) {
+        Ok(jvm_opts) => jvm_opts,
+        Err(MemoryCalculatorError::InsufficientMemory {
+            total_memory,
+            non_heap_memory,
+        }) => {
+            // The output of exec.d programs is written to file descriptor 3, stderr is shown in
+            // the application logs.
+            eprintln!(
+                "Warning: The memory limit of {total_memory} bytes is not large enough for the \
+                estimated {non_heap_memory} bytes of non-heap memory. Falling back to JVM \
+                ergonomics. Consider lowering {THREAD_COUNT_ENV_VAR_NAME} or \
+                {HEADROOM_ENV_VAR_NAME}."
+            );
+
+            ergonomics_jvm_opts()
+        }
+    }
+}
+
+// Rely on JVM ergonomics when the memory limit is unknown, but increase the maximum RAM percentage
+// from 25% to 80%. This is to ensure max heap configuration is aligned to the existing Heroku JVM
+// buildpacks.
+fn ergonomics_jvm_opts() -> Vec<String> {
+    vec![String::from("-XX:MaxRAMPercentage=80.0")]
+}
+
+// JVM options the user configured. These take precedence over the calculated options.
+fn user_jvm_options(env: &Env) -> String {
+    ["JAVA_TOOL_OPTIONS", "JDK_JAVA_OPTIONS", "JAVA_OPTS"]
+        .into_iter()
+        .filter_map(|name| env.get(name))
+        .map(|value| value.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn env_var_number(env: &Env, name: &str) -> Option<u64> {
+    env.get(name)
+        .and_then(|value| value.to_string_lossy().trim().parse().ok())
+}
+
+const SYSFS_ROOT: &str = "/sys";
+const HEADROOM_ENV_VAR_NAME: &str = "JVM_MEMORY_HEADROOM";
+const THREAD_COUNT_ENV_VAR_NAME: &str = "JVM_THREAD_COUNT";
+const LOADED_CLASS_COUNT_ENV_VAR_NAME: &str = "JVM_LOADED_CLASS_COUNT";
+const DEFAULT_HEADROOM_PERCENTAGE: u64 = 0;
+const DEFAULT_THREAD_COUNT: u64 = 250;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Creates a fake sysfs root with the given files in its `fs/cgroup` directory.
+    fn sysfs_root(cgroup_files: &[(&str, &str)]) -> TempDir {
+        let sysfs_root = tempfile::tempdir().unwrap();
+
+        for (path, contents) in cgroup_files {
+            let path = sysfs_root.path().join("fs/cgroup").join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        sysfs_root
+    }
+
+    fn java_tool_options(env: &Env, sysfs_root: &TempDir) -> String {
+        output_from_env(env, sysfs_root.path(), sysfs_root.path())
+            .remove(&exec_d_program_output_key!("JAVA_TOOL_OPTIONS"))
+            .unwrap()
+    }
+
+    fn env_with_class_count() -> Env {
+        let mut env = Env::new();
+        env.insert(LOADED_CLASS_COUNT_ENV_VAR_NAME, "10000");
+        env
+    }
+
+    #[test]
+    fn java_tool_options_are_appended() {
+        let sysfs_root = sysfs_root(&[("memory.max", "1073741824\n")]);
+
+        let mut env = env_with_class_count();
+        env.insert("JAVA_TOOL_OPTIONS", "-Dfile.encoding=UTF-8");
+        env.insert("JAVA_OPTS", "-Xss256k");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root),
+            "-Xmx657599k -XX:MaxMetaspaceSize=70976k -XX:ReservedCodeCacheSize=245760k -XX:MaxDirectMemorySize=10240k -Dfile.encoding=UTF-8"
+        );
+    }
+
+    #[test]
+    fn user_heap_settings_are_respected() {
+        let sysfs_root = sysfs_root(&[("memory.max", "1073741824\n")]);
+
+        for user_heap_option in [
+            "-XX:MaxRAMPercentage=60.0",
+            "-XX:InitialRAMPercentage=50.0",
+            "-XX:MaxHeapSize=512m",
+        ] {
+            let mut env = env_with_class_count();
+            env.insert("JAVA_OPTS", user_heap_option);
+
+            assert_eq!(
+                java_tool_options(&env, &sysfs_root),
+                "-Xss1024k -XX:MaxMetaspaceSize=70976k -XX:ReservedCodeCacheSize=245760k -XX:MaxDirectMemorySize=10240k"
+            );
+        }
+    }
+
+    #[test]
+    fn dyno_defaults() {
+        assert_eq!(
+            java_tool_options(&Env::new(), &sysfs_root(&[("memory.max", "536870912\n")])),
+            "-Xmx300m -Xss512k -XX:CICompilerCount=2"
+        );
+
+        assert_eq!(
+            java_tool_options(
+                &Env::new(),
+                &sysfs_root(&[
+                    ("memory/memory.limit_in_bytes", "1073741824\n"),
+                    ("cpu.max", "200000 100000\n")
+                ])
+            ),
+            "-Xmx671m -XX:CICompilerCount=2 -XX:ActiveProcessorCount=2 -XX:+UseParallelGC"
+        );
+
+        // The application class count from the build does not configure the memory calculator.
+        let mut env = Env::new();
+        env.insert(APPLICATION_CLASS_COUNT_ENV_VAR_NAME, "5000");
+        env.insert("JAVA_OPTS", "-Dfile.encoding=UTF-8");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root(&[("memory.max", "1073741824\n")])),
+            "-Xmx671m -XX:CICompilerCount=2"
+        );
+    }
+
+    #[test]
+    fn memory_calculator_on_dynos() {
+        let sysfs_root = sysfs_root(&[("memory.max", "536870912\n")]);
+
+        let mut env = Env::new();
+        env.insert(HEADROOM_ENV_VAR_NAME, "5");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root),
+            "-Xmx246785k -Xss512k -XX:MaxMetaspaceSize=63896k -XX:ReservedCodeCacheSize=49152k -XX:MaxDirectMemorySize=10240k"
+        );
+
+        let mut env = Env::new();
+        env.insert("JAVA_OPTS", "-XX:MaxRAMPercentage=60.0");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root),
+            "-Xss512k -XX:MaxMetaspaceSize=63896k -XX:ReservedCodeCacheSize=49152k -XX:MaxDirectMemorySize=10240k"
+        );
+    }
+
+    #[test]
+    fn ergonomics_without_memory_limit() {
+        assert_eq!(
+            java_tool_options(&Env::new(), &sysfs_root(&[])),
+            "-XX:MaxRAMPercentage=80.0"
+        );
+
+        let mut env = Env::new();
+        env.insert(THREAD_COUNT_ENV_VAR_NAME, "1000");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root(&[("memory.max", "268435456\n")])),
+            "-XX:MaxRAMPercentage=80.0"
+        );
+    }
+
+    #[test]
+    fn cgroup_v2_with_single_cpu() {
+        let sysfs_root = sysfs_root(&[
+            ("memory.max", "536870912\n"),
+            ("cpu.max", "100000 100000\n"),
+        ]);
+
+        assert_eq!(
+            java_tool_options(&env_with_class_count(), &sysfs_root),
+            "-Xmx265919k -Xss512k -XX:MaxMetaspaceSize=70976k -XX:ReservedCodeCacheSize=49152k -XX:MaxDirectMemorySize=10240k -XX:ActiveProcessorCount=1 -XX:CICompilerCount=2"
+        );
+    }
+
+    #[test]
+    fn cgroup_v2_without_cpu_limit() {
+        let sysfs_root = sysfs_root(&[("memory.max", "2147483648\n"), ("cpu.max", "max 100000\n")]);
+
+        assert_eq!(
+            java_tool_options(&env_with_class_count(), &sysfs_root),
+            "-Xmx1514175k -Xss1024k -XX:MaxMetaspaceSize=70976k -XX:ReservedCodeCacheSize=245760k -XX:MaxDirectMemorySize=10240k"
+        );
+    }
+
+    #[test]
+    fn cgroup_v1_with_multiple_cpus() {
+        let sysfs_root = sysfs_root(&[
+            ("memory/memory.limit_in_bytes", "1073741824\n"),
+            ("cpu,cpuacct/cpu.cfs_quota_us", "250000\n"),
+            ("cpu,cpuacct/cpu.cfs_period_us", "100000\n"),
+        ]);
+
+        let mut env = env_with_class_count();
+        env.insert("JAVA_OPTS", "-Xmx512m");
+        env.insert("JDK_JAVA_OPTIONS", "-XX:+UseZGC");
+
+        assert_eq!(
+            java_tool_options(&env, &sysfs_root),
+            "-Xss1024k -XX:MaxMetaspaceSize=70976k -XX:ReservedCodeCacheSize=245760k -XX:MaxDirectMemorySize=10240k -XX:ActiveProcessorCount=3 -XX:CICompilerCount=2"
+        );
+    }
+
+    #[test]
+    fn cpu_limit_without_memory_limit() {
+        let sysfs_root = sysfs_root(&[("cpu.max", "800000 100000\n")]);
+
+        assert_eq!(
+            java_tool_options(&Env::new(), &sysfs_root),
+            "-XX:MaxRAMPercentage=80.0 -XX:ActiveProcessorCount=8 -XX:CICompilerCount=4"
+        );
+    }
+}
@@ -0,0 +1,426 @@
+use std::path::Path;
+
+/// The user configurable inputs of the memory calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MemoryCalculatorConfig {
+    /// The percentage of the total memory that is not allocated to the JVM.
+    pub(crate) headroom_percentage: u64,
+    /// The number of threads the application is expected to use.
+    pub(crate) thread_count: u64,
+    /// The number of classes the application is expected to load.
+    pub(crate) loaded_class_count: u64,
+    /// Memory options that were already configured by the user. These are used as-is.
+    pub(crate) user_options: JvmMemoryOptions,
+}
+
+/// The sizes of the JVM memory regions, in bytes.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct JvmMemoryOptions {
+    pub(crate) heap: Option<u64>,
+    /// Whether the heap is sized relative to the memory limit, with `-XX:MaxRAMPercentage` or
+    /// `-XX:InitialRAMPercentage`.
+    pub(crate) heap_ram_percentage: bool,
+    pub(crate) thread_stack: Option<u64>,
+    pub(crate) metaspace: Option<u64>,
+    pub(crate) code_cache: Option<u64>,
+    pub(crate) direct_memory: Option<u64>,
+}
+
+impl JvmMemoryOptions {
+    /// Reads the memory options from JVM options, such as the value of `JAVA_OPTS`. Later options
+    /// override earlier ones, as they would for the JVM.
+    pub(crate) fn parse(jvm_options: &str) -> Self {
+        let mut options = JvmMemoryOptions::default();
+
+        for option in jvm_options.split_whitespace() {
+            if let Some(value) = option
+                .strip_prefix("-Xmx")
+                .or_else(|| option.strip_prefix("-XX:MaxHeapSize="))
+            {
+                options.heap = parse_size(value).or(options.heap);
+            } else if option.starts_with("-XX:MaxRAMPercentage=")
+                || option.starts_with("-XX:InitialRAMPercentage=")
+            {
+                options.heap_ram_percentage = true;
+            } else if let Some(value) = option.strip_prefix("-Xss") {
+                options.thread_stack = parse_size(value).or(options.thread_stack);
+            } else if let Some(value) = option.strip_prefix("-XX:MaxMetaspaceSize=") {
+                options.metaspace = parse_size(value).or(options.metaspace);
+            } else if let Some(value) = option.strip_prefix("-XX:ReservedCodeCacheSize=") {
+                options.code_cache = parse_size(value).or(options.code_cache);
+            } else if let Some(value) = option.strip_prefix("-XX:MaxDirectMemorySize=") {
+                options.direct_memory = parse_size(value).or(options.direct_memory);
+            }
+        }
+
+        options
+    }
+}
+
+/// Divides the total memory between the JVM memory regions. Regions that the user configured keep
+/// their size, the heap gets all memory that is left after the other regions and the headroom.
+///
+/// Returns the JVM options for the regions the user did not configure.
+pub(crate) fn calculate_jvm_memory_options(
+    total_memory: u64,
+    config: &MemoryCalculatorConfig,
+) -> Result<Vec<String>, MemoryCalculatorError> {
+    let user_options = &config.user_options;
+    let small_container = total_memory < SMALL_CONTAINER_MEMORY_LIMIT;
+
+    let thread_stack = user_options.thread_stack.unwrap_or(if small_container {
+        SMALL_CONTAINER_THREAD_STACK_SIZE
+    } else {
+        DEFAULT_THREAD_STACK_SIZE
+    });
+
+    let metaspace = user_options.metaspace.or_else(|| {
+        config
+            .loaded_class_count
+            .checked_mul(METASPACE_BYTES_PER_CLASS)?
+            .checked_add(METASPACE_OVERHEAD)
+    });
+
+    let code_cache = user_options.code_cache.unwrap_or(if small_container {
+        SMALL_CONTAINER_CODE_CACHE_SIZE
+    } else {
+        DEFAULT_CODE_CACHE_SIZE
+    });
+
+    let direct_memory = user_options
+        .direct_memory
+        .unwrap_or(DEFAULT_DIRECT_MEMORY_SIZE);
+
+    let headroom = total_memory / 100 * config.headroom_percentage.min(100);
+
+    // Sizes that do not fit into 64 bits never fit into the memory limit either.
+    let non_heap_memory = metaspace
+        .and_then(|metaspace| {
+            thread_stack
+                .checked_mul(config.thread_count)?
+                .checked_add(headroom)?
+                .checked_add(metaspace)?
+                .checked_add(code_cache)?
+                .checked_add(direct_memory)
+        })
+        .unwrap_or(u64::MAX);
+
+    let heap = if user_options.heap.is_some() || user_options.heap_ram_percentage {
+        None
+    } else {
+        Some(
+            total_memory
+                .checked_sub(non_heap_memory)
+                .filter(|heap| *heap > 0)
+                .ok_or(MemoryCalculatorError::InsufficientMemory {
+                    total_memory,
+                    non_heap_memory,
+                })?,
+        )
+    };
+
+    Ok([
+        ("-Xmx", heap),
+        (
+            "-Xss",
+            user_options.thread_stack.is_none().then_some(thread_stack),
+        ),
+        (
+            "-XX:MaxMetaspaceSize=",
+            metaspace.filter(|_| user_options.metaspace.is_none()),
+        ),
+        (
+            "-XX:ReservedCodeCacheSize=",
+            user_options.code_cache.is_none().then_some(code_cache),
+        ),
+        (
+            "-XX:MaxDirectMemorySize=",
+            user_options
+                .direct_memory
+                .is_none()
+                .then_some(direct_memory),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(option, size)| size.map(|size| format!("{option}{}k", size / 1024)))
+    .collect())
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum MemoryCalculatorError {
+    InsufficientMemory {
+        total_memory: u64,
+        non_heap_memory: u64,
+    },
+}
+
+/// Reads the memory limit of the container from the cgroup v2 (`memory.max`) or cgroup v1
+/// (`memory/memory.limit_in_bytes`) files in `cgroup_dir`. Unlimited memory is reported as `None`.
+pub(crate) fn detect_memory_limit(cgroup_dir: &Path) -> Option<u64> {
+    ["memory.max", "memory/memory.limit_in_bytes"]
+        .into_iter()
+        .find_map(|path| std::fs::read_to_string(cgroup_dir.join(path)).ok())
+        // cgroup v2 uses "max" for unlimited memory, which fails to parse. cgroup v1 uses the
+        // largest page aligned 64-bit value instead.
+        .and_then(|contents| contents.trim().parse().ok())
+        .filter(|limit| *limit < CGROUP_V1_UNLIMITED_MEMORY_THRESHOLD)
+}
+
+/// Estimates the number of classes the application loads from the number of classes of the
+/// application. Like the Paketo memory calculator, it assumes that 35% of the application and JDK
+/// classes are loaded.
+pub(crate) fn estimate_loaded_class_count(application_class_count: u64) -> u64 {
+    application_class_count
+        .saturating_add(JDK_CLASS_COUNT)
+        .saturating_mul(LOADED_CLASS_PERCENTAGE)
+        / 100
+}
+
+/// Parses a JVM memory size, such as `512m` or `1G`. Sizes without a unit are in bytes.
+fn parse_size(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1024),
+        'm' | 'M' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' | 'G' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        't' | 'T' => (&value[..value.len() - 1], 1024 * 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+const MIB: u64 = 1024 * 1024;
+
+// Containers below this limit use smaller thread stacks and code caches, to leave enough memory
+// for the heap.
+const SMALL_CONTAINER_MEMORY_LIMIT: u64 = 1024 * MIB;
+const DEFAULT_THREAD_STACK_SIZE: u64 = MIB;
+const SMALL_CONTAINER_THREAD_STACK_SIZE: u64 = MIB / 2;
+const DEFAULT_CODE_CACHE_SIZE: u64 = 240 * MIB;
+const SMALL_CONTAINER_CODE_CACHE_SIZE: u64 = 48 * MIB;
+const DEFAULT_DIRECT_MEMORY_SIZE: u64 = 10 * MIB;
+
+// Metaspace estimation as used by the Paketo memory calculator.
+const METASPACE_BYTES_PER_CLASS: u64 = 5800;
+const METASPACE_OVERHEAD: u64 = 14 * MIB;
+const LOADED_CLASS_PERCENTAGE: u64 = 35;
+const JDK_CLASS_COUNT: u64 = 25_000;
+
+const CGROUP_V1_UNLIMITED_MEMORY_THRESHOLD: u64 = 1 << 62;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(thread_count: u64, user_options: JvmMemoryOptions) -> MemoryCalculatorConfig {
+        MemoryCalculatorConfig {
+            headroom_percentage: 0,
+            thread_count,
+            loaded_class_count: 10_000,
+            user_options,
+        }
+    }
+
+    #[test]
+    fn parse_jvm_memory_options() {
+        assert_eq!(
+            JvmMemoryOptions::parse(
+                "-Dfile.encoding=UTF-8 -Xmx512m -Xss256K -XX:MaxMetaspaceSize=100M -Xmx1g -XX:MaxDirectMemorySize=invalid"
+            ),
+            JvmMemoryOptions {
+                heap: Some(1024 * MIB),
+                heap_ram_percentage: false,
+                thread_stack: Some(256 * 1024),
+                metaspace: Some(100 * MIB),
+                code_cache: None,
+                direct_memory: None,
+            }
+        );
+
+        assert_eq!(
+            JvmMemoryOptions::parse("-XX:MaxHeapSize=768m"),
+            JvmMemoryOptions {
+                heap: Some(768 * MIB),
+                ..JvmMemoryOptions::default()
+            }
+        );
+
+        for options in ["-XX:MaxRAMPercentage=60.0", "-XX:InitialRAMPercentage=50"] {
+            assert_eq!(
+                JvmMemoryOptions::parse(options),
+                JvmMemoryOptions {
+                    heap_ram_percentage: true,
+                    ..JvmMemoryOptions::default()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn calculate_for_large_container() {
+        // metaspace: 10000 * 5800 + 14MiB = 72680064 bytes
+        // non-heap: 250 * 1MiB + 72680064 + 240MiB + 10MiB = 596968064 bytes
+        assert_eq!(
+            calculate_jvm_memory_options(2048 * MIB, &config(250, JvmMemoryOptions::default())),
+            Ok(vec![
+                String::from("-Xmx1514175k"),
+                String::from("-Xss1024k"),
+                String::from("-XX:MaxMetaspaceSize=70976k"),
+                String::from("-XX:ReservedCodeCacheSize=245760k"),
+                String::from("-XX:MaxDirectMemorySize=10240k"),
+            ])
+        );
+    }
+
+    #[test]
+    fn calculate_for_small_container() {
+        assert_eq!(
+            calculate_jvm_memory_options(512 * MIB, &config(250, JvmMemoryOptions::default())),
+            Ok(vec![
+                String::from("-Xmx265919k"),
+                String::from("-Xss512k"),
+                String::from("-XX:MaxMetaspaceSize=70976k"),
+                String::from("-XX:ReservedCodeCacheSize=49152k"),
+                String::from("-XX:MaxDirectMemorySize=10240k"),
+            ])
+        );
+    }
+
+    #[test]
+    fn calculate_with_headroom_and_user_options() {
+        let mut config = config(
+            100,
+            JvmMemoryOptions {
+                thread_stack: Some(MIB / 4),
+                metaspace: Some(128 * MIB),
+                ..JvmMemoryOptions::default()
+            },
+        );
+        config.headroom_percentage = 10;
+
+        // non-heap: 10% of 1GiB + 100 * 256KiB + 128MiB + 240MiB + 10MiB
+        assert_eq!(
+            calculate_jvm_memory_options(1024 * MIB, &config),
+            Ok(vec![
+                String::from("-Xmx531046k"),
+                String::from("-XX:ReservedCodeCacheSize=245760k"),
+                String::from("-XX:MaxDirectMemorySize=10240k"),
+            ])
+        );
+    }
+
+    #[test]
+    fn calculate_with_insufficient_memory() {
+        assert_eq!(
+            calculate_jvm_memory_options(256 * MIB, &config(1000, JvmMemoryOptions::default())),
+            Err(MemoryCalculatorError::InsufficientMemory {
+                total_memory: 256 * MIB,
+                non_heap_memory: 1000 * MIB / 2 + 72_680_064 + 48 * MIB + 10 * MIB,
+            })
+        );
+
+        // A heap size configured by the user is never changed.
+        assert_eq!(
+            calculate_jvm_memory_options(
+                256 * MIB,
+                &config(
+                    1000,
+                    JvmMemoryOptions {
+                        heap: Some(128 * MIB),
+                        ..JvmMemoryOptions::default()
+                    }
+                )
+            )
+            .map(|options| options.len()),
+            Ok(4)
+        );
+
+        // The same applies to a heap that is sized relative to the memory limit.
+        assert_eq!(
+            calculate_jvm_memory_options(
+                256 * MIB,
+                &config(
+                    1000,
+                    JvmMemoryOptions {
+                        heap_ram_percentage: true,
+                        ..JvmMemoryOptions::default()
+                    }
+                )
+            ),
+            Ok(vec![
+                String::from("-Xss512k"),
+                String::from("-XX:MaxMetaspaceSize=70976k"),
+                String::from("-XX:ReservedCodeCacheSize=49152k"),
+                String::from("-XX:MaxDirectMemorySize=10240k"),
+            ])
+        );
+    }
+
+    #[test]
+    fn memory_limit_from_cgroups() {
+        let cgroup_dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect_memory_limit(cgroup_dir.path()), None);
+
+        std::fs::create_dir(cgroup_dir.path().join("memory")).unwrap();
+        std::fs::write(
+            cgroup_dir.path().join("memory/memory.limit_in_bytes"),
+            "9223372036854771712\n",
+        )
+        .unwrap();
+        assert_eq!(detect_memory_limit(cgroup_dir.path()), None);
+
+        std::fs::write(
+            cgroup_dir.path().join("memory/memory.limit_in_bytes"),
+            "1073741824\n",
+        )
+        .unwrap();
+        assert_eq!(detect_memory_limit(cgroup_dir.path()), Some(1024 * MIB));
+
+        std::fs::write(cgroup_dir.path().join("memory.max"), "max\n").unwrap();
+        assert_eq!(detect_memory_limit(cgroup_dir.path()), None);
+
+        std::fs::write(cgroup_dir.path().join("memory.max"), "536870912\n").unwrap();
+        assert_eq!(detect_memory_limit(cgroup_dir.path()), Some(512 * MIB));
+    }
+
+    #[test]
+    fn loaded_class_count_estimate() {
+        assert_eq!(estimate_loaded_class_count(0), 8750);
+        assert_eq!(estimate_loaded_class_count(1000), 9100);
+        assert_eq!(estimate_loaded_class_count(u64::MAX), u64::MAX / 100);
+    }
+
+    #[test]
+    fn calculate_with_overflowing_sizes() {
+        assert_eq!(parse_size("20000000000000T"), None);
+        assert_eq!(parse_size("17179869184G"), None);
+
+        assert_eq!(
+            calculate_jvm_memory_options(
+                1024 * MIB,
+                &config(
+                    u64::MAX,
+                    JvmMemoryOptions {
+                        thread_stack: Some(MIB),
+                        ..JvmMemoryOptions::default()
+                    }
+                )
+            ),
+            Err(MemoryCalculatorError::InsufficientMemory {
+                total_memory: 1024 * MIB,
+                non_heap_memory: u64::MAX,
+            })
+        );
+
+        let mut config = config(250, JvmMemoryOptions::default());
+        config.loaded_class_count = u64::MAX;
+
+        assert_eq!(
+            calculate_jvm_memory_options(1024 * MIB, &config),
+            Err(MemoryCalculatorError::InsufficientMemory {
+                total_memory: 1024 * MIB,
+                non_heap_memory: u64::MAX,
+            })
+        );
+    }
+}
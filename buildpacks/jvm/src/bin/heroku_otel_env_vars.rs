@@ -0,0 +1,205 @@
+// Required due to: https://github.com/rust-lang/rust/issues/95513
+#![allow(unused_crate_dependencies)]
+
+use libcnb::data::exec_d::ExecDProgramOutputKey;
+use libcnb::exec_d::write_exec_d_program_output;
+use std::collections::HashMap;
+
+fn main() {
+    write_exec_d_program_output(
+        otel_env_vars_for_env(&std::env::vars().collect())
+            .into_iter()
+            .filter_map(|(key, value)| key.parse().ok().map(|key| (key, value)))
+            .collect::<HashMap<ExecDProgramOutputKey, String>>(),
+    );
+}
+
+/// Configures the OpenTelemetry Java agent with the dyno metadata that is available at runtime.
+/// Environment variables the user already set are never overwritten. Resource attributes are only
+/// added if the user didn't configure an attribute with the same key.
+fn otel_env_vars_for_env(input: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+
+    if !input.contains_key("OTEL_SERVICE_NAME")
+        && let Some(app_name) = input.get("HEROKU_APP_NAME")
+    {
+        result.insert(String::from("OTEL_SERVICE_NAME"), app_name.clone());
+    }
+
+    let user_resource_attributes = input
+        .get("OTEL_RESOURCE_ATTRIBUTES")
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty());
+
+    let user_resource_attribute_keys = user_resource_attributes
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|attribute| attribute.split_once('='))
+                .map(|(key, _)| key.trim())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let resource_attributes = RESOURCE_ATTRIBUTE_MAPPINGS
+        .iter()
+        .filter(|(_, attribute_key)| !user_resource_attribute_keys.contains(attribute_key))
+        .filter_map(|(env_var_name, attribute_key)| {
+            input
+                .get(*env_var_name)
+                .filter(|value| !value.is_empty())
+                .map(|value| {
+                    format!(
+                        "{attribute_key}={}",
+                        percent_encode_resource_attribute_value(value)
+                    )
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if !resource_attributes.is_empty() {
+        result.insert(
+            String::from("OTEL_RESOURCE_ATTRIBUTES"),
+            user_resource_attributes
+                .into_iter()
+                .map(String::from)
+                .chain(resource_attributes)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    result
+}
+
+// Resource attributes are separated by `,` and their key and value by `=`. Values that contain these
+// characters must be percent-encoded, which in turn requires encoding `%` itself.
+fn percent_encode_resource_attribute_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|char| match char {
+            '%' => String::from("%25"),
+            ',' => String::from("%2C"),
+            '=' => String::from("%3D"),
+            _ => String::from(char),
+        })
+        .collect()
+}
+
+// Environment variables set by Heroku (some of them require the dyno metadata labs feature) and
+// the OpenTelemetry resource attributes they are mapped to.
+const RESOURCE_ATTRIBUTE_MAPPINGS: [(&str, &str); 6] = [
+    ("DYNO", "service.instance.id"),
+    ("HEROKU_RELEASE_VERSION", "service.version"),
+    ("HEROKU_APP_NAME", "heroku.app.name"),
+    ("HEROKU_APP_ID", "heroku.app.id"),
+    ("HEROKU_SLUG_COMMIT", "heroku.release.commit"),
+    (
+        "HEROKU_RELEASE_CREATED_AT",
+        "heroku.release.creation_timestamp",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::otel_env_vars_for_env;
+    use std::collections::HashMap;
+
+    fn dyno_metadata_env() -> HashMap<String, String> {
+        HashMap::from([
+            (String::from("DYNO"), String::from("web.1")),
+            (String::from("HEROKU_APP_NAME"), String::from("example-app")),
+            (
+                String::from("HEROKU_APP_ID"),
+                String::from("9daa2797-e49b-4624-932f-ec3f9688e3da"),
+            ),
+            (String::from("HEROKU_RELEASE_VERSION"), String::from("v42")),
+        ])
+    }
+
+    #[test]
+    fn dyno_metadata_mapping() {
+        let result = otel_env_vars_for_env(&dyno_metadata_env());
+
+        assert_eq!(
+            result.get("OTEL_SERVICE_NAME"),
+            Some(&String::from("example-app"))
+        );
+        assert_eq!(
+            result.get("OTEL_RESOURCE_ATTRIBUTES"),
+            Some(&String::from(
+                "service.instance.id=web.1,service.version=v42,heroku.app.name=example-app,heroku.app.id=9daa2797-e49b-4624-932f-ec3f9688e3da"
+            ))
+        );
+    }
+
+    #[test]
+    fn dyno_only() {
+        let result = otel_env_vars_for_env(&HashMap::from([(
+            String::from("DYNO"),
+            String::from("worker.2"),
+        )]));
+
+        assert_eq!(result.get("OTEL_SERVICE_NAME"), None);
+        assert_eq!(
+            result.get("OTEL_RESOURCE_ATTRIBUTES"),
+            Some(&String::from("service.instance.id=worker.2"))
+        );
+    }
+
+    #[test]
+    fn resource_attribute_values_are_percent_encoded() {
+        let result = otel_env_vars_for_env(&HashMap::from([
+            (String::from("DYNO"), String::from("web.1")),
+            (
+                String::from("HEROKU_RELEASE_VERSION"),
+                String::from("v1,key=100%"),
+            ),
+        ]));
+
+        assert_eq!(
+            result.get("OTEL_RESOURCE_ATTRIBUTES"),
+            Some(&String::from(
+                "service.instance.id=web.1,service.version=v1%2Ckey%3D100%25"
+            ))
+        );
+    }
+
+    #[test]
+    fn no_dyno_metadata() {
+        assert_eq!(otel_env_vars_for_env(&HashMap::new()), HashMap::new());
+    }
+
+    #[test]
+    fn user_values_are_kept() {
+        let mut env = dyno_metadata_env();
+        env.insert(String::from("OTEL_SERVICE_NAME"), String::from("checkout"));
+        env.insert(
+            String::from("OTEL_RESOURCE_ATTRIBUTES"),
+            String::from("deployment.environment=production,service.version=1.2.3"),
+        );
+
+        let result = otel_env_vars_for_env(&env);
+
+        assert_eq!(result.get("OTEL_SERVICE_NAME"), None);
+        assert_eq!(
+            result.get("OTEL_RESOURCE_ATTRIBUTES"),
+            Some(&String::from(
+                "deployment.environment=production,service.version=1.2.3,service.instance.id=web.1,heroku.app.name=example-app,heroku.app.id=9daa2797-e49b-4624-932f-ec3f9688e3da"
+            ))
+        );
+    }
+
+    #[test]
+    fn user_resource_attributes_cover_all_keys() {
+        let result = otel_env_vars_for_env(&HashMap::from([
+            (String::from("DYNO"), String::from("web.1")),
+            (
+                String::from("OTEL_RESOURCE_ATTRIBUTES"),
+                String::from("service.instance.id=custom"),
+            ),
+        ]));
+
+        assert_eq!(result.get("OTEL_RESOURCE_ATTRIBUTES"), None);
+    }
+}
@@ -0,0 +1,495 @@
+use p12_keystore::{Certificate, KeyStore, KeyStoreEntry, PrivateKeyChain};
+use pkcs8::der::Encode;
+use pkcs8::der::asn1::AnyRef;
+use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier, PrivateKeyInfo};
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use std::collections::HashMap;
+use std::fs::Permissions;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// Maps the broker URLs of Apache Kafka on Heroku to the Spring Kafka bootstrap servers. Spring
+/// Kafka expects `host:port` pairs, the `kafka+ssl://` scheme of the URLs is therefore removed.
+///
+/// Hostname verification stays enabled unless it is explicitly disabled with
+/// `DISABLE_SPRING_KAFKA_HOSTNAME_VERIFICATION=true`.
+pub(crate) fn kafka_env_vars(input: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+
+    if is_disabled(input) {
+        return result;
+    }
+
+    if let Some(kafka_url) = input.get("KAFKA_URL") {
+        if !input.contains_key("SPRING_KAFKA_BOOTSTRAP_SERVERS") {
+            result.insert(
+                String::from("SPRING_KAFKA_BOOTSTRAP_SERVERS"),
+                kafka_url
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(|url| {
+                        url.strip_prefix(KAFKA_SSL_URL_SCHEME_PREFIX)
+                            .or_else(|| url.strip_prefix("kafka://"))
+                            .unwrap_or(url)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        if kafka_url.contains(KAFKA_SSL_URL_SCHEME_PREFIX) {
+            // Without this, Spring Kafka connects in plaintext and ignores the SSL configuration.
+            if !input.contains_key("SPRING_KAFKA_SECURITY_PROTOCOL") {
+                result.insert(
+                    String::from("SPRING_KAFKA_SECURITY_PROTOCOL"),
+                    String::from("SSL"),
+                );
+            }
+
+            // Brokers whose certificates don't contain their host names fail hostname
+            // verification, which is enabled by default since Kafka 2.0.
+            if is_enabled(input, DISABLE_HOSTNAME_VERIFICATION_ENV_VAR_NAME)
+                && !input.contains_key(SSL_ENDPOINT_IDENTIFICATION_ALGORITHM_ENV_VAR_NAME)
+            {
+                result.insert(
+                    String::from(SSL_ENDPOINT_IDENTIFICATION_ALGORITHM_ENV_VAR_NAME),
+                    String::new(),
+                );
+            }
+        }
+    }
+
+    result
+}
+
+fn is_disabled(input: &HashMap<String, String>) -> bool {
+    is_enabled(input, DISABLE_ENV_VAR_NAME)
+}
+
+fn is_enabled(input: &HashMap<String, String>, env_var_name: &str) -> bool {
+    input.get(env_var_name).is_some_and(|value| value == "true")
+}
+
+/// Writes the Apache Kafka on Heroku certificates into PKCS12 trust and key stores in
+/// `keystore_dir` and returns the Spring Kafka environment variables that point to them. The
+/// directory is created if necessary, existing stores are replaced. Stores that the user already
+/// configured are not written.
+pub(crate) fn kafka_ssl_env_vars(
+    input: &HashMap<String, String>,
+    keystore_dir: &Path,
+) -> Result<HashMap<String, String>, KafkaEnvVarError> {
+    let mut result = HashMap::new();
+
+    if is_disabled(input) {
+        return Ok(result);
+    }
+
+    let trusted_cert = input
+        .get("KAFKA_TRUSTED_CERT")
+        .filter(|_| !input.contains_key("SPRING_KAFKA_SSL_TRUST_STORE_LOCATION"));
+
+    let client_cert = input
+        .get("KAFKA_CLIENT_CERT")
+        .zip(input.get("KAFKA_CLIENT_CERT_KEY"))
+        .filter(|_| !input.contains_key("SPRING_KAFKA_SSL_KEY_STORE_LOCATION"));
+
+    if trusted_cert.is_none() && client_cert.is_none() {
+        return Ok(result);
+    }
+
+    // The key store contains the private key, only the application user may read the stores.
+    std::fs::create_dir_all(keystore_dir)?;
+    std::fs::set_permissions(keystore_dir, Permissions::from_mode(0o700))?;
+
+    if let Some(trusted_cert) = trusted_cert {
+        let mut trust_store = KeyStore::new();
+
+        for (index, certificate) in parse_certificates(trusted_cert, "KAFKA_TRUSTED_CERT")?
+            .into_iter()
+            .enumerate()
+        {
+            trust_store.add_entry(
+                &format!("kafka-trusted-cert-{index}"),
+                KeyStoreEntry::Certificate(certificate),
+            );
+        }
+
+        result.extend(write_store(
+            &trust_store,
+            &keystore_dir.join("truststore.p12"),
+            "SPRING_KAFKA_SSL_TRUST_STORE",
+        )?);
+    }
+
+    if let Some((client_cert, client_cert_key)) = client_cert {
+        let mut key_store = KeyStore::new();
+
+        key_store.add_entry(
+            "kafka-client",
+            KeyStoreEntry::PrivateKeyChain(PrivateKeyChain::new(
+                parse_private_key(client_cert_key)?,
+                LOCAL_KEY_ID,
+                parse_certificates(client_cert, "KAFKA_CLIENT_CERT")?,
+            )),
+        );
+
+        let key_store_env_vars = write_store(
+            &key_store,
+            &keystore_dir.join("keystore.p12"),
+            "SPRING_KAFKA_SSL_KEY_STORE",
+        )?;
+
+        // The private key in a PKCS12 key store is protected by the store password.
+        if let Some(password) = key_store_env_vars.get("SPRING_KAFKA_SSL_KEY_STORE_PASSWORD") {
+            result.insert(
+                String::from("SPRING_KAFKA_SSL_KEY_PASSWORD"),
+                password.clone(),
+            );
+        }
+
+        result.extend(key_store_env_vars);
+    }
+
+    Ok(result)
+}
+
+/// Writes the store with a random password and returns the location, password and type
+/// environment variables with the given prefix.
+fn write_store(
+    store: &KeyStore,
+    path: &Path,
+    env_var_prefix: &str,
+) -> Result<HashMap<String, String>, KafkaEnvVarError> {
+    let password = rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect::<String>();
+
+    std::fs::write(path, store.writer(&password).write()?)?;
+
+    Ok(HashMap::from([
+        (
+            format!("{env_var_prefix}_LOCATION"),
+            // Spring resolves locations without a prefix from the classpath.
+            format!("file:{}", path.to_string_lossy()),
+        ),
+        (format!("{env_var_prefix}_PASSWORD"), password),
+        (format!("{env_var_prefix}_TYPE"), String::from("PKCS12")),
+    ]))
+}
+
+fn parse_certificates(
+    value: &str,
+    env_var_name: &'static str,
+) -> Result<Vec<Certificate>, KafkaEnvVarError> {
+    let certificates = pem::parse_many(value)?
+        .iter()
+        .filter(|pem| pem.tag() == "CERTIFICATE")
+        .map(|pem| Certificate::from_der(pem.contents()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if certificates.is_empty() {
+        Err(KafkaEnvVarError::MissingCertificate(env_var_name))
+    } else {
+        Ok(certificates)
+    }
+}
+
+/// Parses the private key into the PKCS#8 DER encoding that is used in PKCS12 stores. Apache
+/// Kafka on Heroku provides RSA keys in the PKCS#1 format.
+fn parse_private_key(value: &str) -> Result<Vec<u8>, KafkaEnvVarError> {
+    let pem = pem::parse(value)?;
+
+    match pem.tag() {
+        "PRIVATE KEY" => Ok(pem.into_contents()),
+        "RSA PRIVATE KEY" => Ok(PrivateKeyInfo::new(
+            AlgorithmIdentifierRef {
+                oid: RSA_ENCRYPTION_OID,
+                parameters: Some(AnyRef::NULL),
+            },
+            pem.contents(),
+        )
+        .to_der()?),
+        tag => Err(KafkaEnvVarError::UnsupportedPrivateKey(String::from(tag))),
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum KafkaEnvVarError {
+    #[error("Cannot write Kafka key store: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Cannot parse Kafka certificate: {0}")]
+    Pem(#[from] pem::PemError),
+    #[error("No certificate found in {0}")]
+    MissingCertificate(&'static str),
+    #[error("Unsupported private key type in KAFKA_CLIENT_CERT_KEY: {0}")]
+    UnsupportedPrivateKey(String),
+    #[error("Cannot encode Kafka private key: {0}")]
+    PrivateKey(#[from] pkcs8::der::Error),
+    #[error("Cannot create Kafka key store: {0}")]
+    KeyStore(#[from] p12_keystore::error::Error),
+}
+
+const DISABLE_ENV_VAR_NAME: &str = "DISABLE_SPRING_KAFKA_CONFIG";
+const DISABLE_HOSTNAME_VERIFICATION_ENV_VAR_NAME: &str =
+    "DISABLE_SPRING_KAFKA_HOSTNAME_VERIFICATION";
+const KAFKA_SSL_URL_SCHEME_PREFIX: &str = "kafka+ssl://";
+const SSL_ENDPOINT_IDENTIFICATION_ALGORITHM_ENV_VAR_NAME: &str =
+    "SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM";
+const LOCAL_KEY_ID: &[u8] = b"kafka-client";
+const RSA_ENCRYPTION_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_CERT: &str = include_str!("test-fixtures/kafka-client-cert.pem");
+    const CLIENT_CERT_KEY: &str = include_str!("test-fixtures/kafka-client-cert-key.pem");
+    const CLIENT_CERT_KEY_PKCS8: &str =
+        include_str!("test-fixtures/kafka-client-cert-key-pkcs8.pem");
+
+    fn heroku_kafka_env() -> HashMap<String, String> {
+        HashMap::from([
+            (
+                String::from("KAFKA_URL"),
+                String::from(
+                    "kafka+ssl://ec2-1-1-1-1.compute-1.amazonaws.com:9096,kafka+ssl://ec2-2-2-2-2.compute-1.amazonaws.com:9096",
+                ),
+            ),
+            // The test certificate is self-signed, it is both the trusted and the client certificate.
+            (
+                String::from("KAFKA_TRUSTED_CERT"),
+                String::from(CLIENT_CERT),
+            ),
+            (String::from("KAFKA_CLIENT_CERT"), String::from(CLIENT_CERT)),
+            (
+                String::from("KAFKA_CLIENT_CERT_KEY"),
+                String::from(CLIENT_CERT_KEY),
+            ),
+        ])
+    }
+
+    fn read_store(env_vars: &HashMap<String, String>, env_var_prefix: &str) -> KeyStore {
+        assert_eq!(
+            env_vars.get(&format!("{env_var_prefix}_TYPE")),
+            Some(&String::from("PKCS12"))
+        );
+
+        let path = env_vars[&format!("{env_var_prefix}_LOCATION")]
+            .strip_prefix("file:")
+            .unwrap()
+            .to_string();
+
+        KeyStore::from_pkcs12(
+            &std::fs::read(path).unwrap(),
+            &env_vars[&format!("{env_var_prefix}_PASSWORD")],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn bootstrap_servers() {
+        let result = kafka_env_vars(&heroku_kafka_env());
+
+        assert_eq!(
+            result.get("SPRING_KAFKA_BOOTSTRAP_SERVERS"),
+            Some(&String::from(
+                "ec2-1-1-1-1.compute-1.amazonaws.com:9096,ec2-2-2-2-2.compute-1.amazonaws.com:9096"
+            ))
+        );
+        assert_eq!(
+            result.get("SPRING_KAFKA_SECURITY_PROTOCOL"),
+            Some(&String::from("SSL"))
+        );
+        assert_eq!(
+            result.get("SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM"),
+            None
+        );
+    }
+
+    #[test]
+    fn hostname_verification_disabled() {
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("DISABLE_SPRING_KAFKA_HOSTNAME_VERIFICATION"),
+            String::from("true"),
+        );
+
+        assert_eq!(
+            kafka_env_vars(&env)
+                .get("SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM"),
+            Some(&String::new())
+        );
+
+        env.insert(
+            String::from("SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM"),
+            String::from("https"),
+        );
+
+        assert_eq!(
+            kafka_env_vars(&env)
+                .get("SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM"),
+            None
+        );
+    }
+
+    #[test]
+    fn bootstrap_servers_already_set() {
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("SPRING_KAFKA_BOOTSTRAP_SERVERS"),
+            String::from("localhost:9092"),
+        );
+        env.insert(
+            String::from("SPRING_KAFKA_SECURITY_PROTOCOL"),
+            String::from("PLAINTEXT"),
+        );
+        env.insert(
+            String::from("SPRING_KAFKA_PROPERTIES_SSL_ENDPOINT_IDENTIFICATION_ALGORITHM"),
+            String::from("https"),
+        );
+
+        assert_eq!(kafka_env_vars(&env), HashMap::new());
+    }
+
+    #[test]
+    fn key_stores() {
+        let parent_dir = tempfile::tempdir().unwrap();
+        let keystore_dir = parent_dir.path().join(".heroku/kafka");
+        let result = kafka_ssl_env_vars(&heroku_kafka_env(), &keystore_dir).unwrap();
+
+        assert_eq!(
+            result.get("SPRING_KAFKA_SSL_TRUST_STORE_LOCATION"),
+            Some(&format!(
+                "file:{}",
+                keystore_dir.join("truststore.p12").to_string_lossy()
+            ))
+        );
+        assert_eq!(
+            std::fs::metadata(&keystore_dir)
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777,
+            0o700
+        );
+
+        let trust_store = read_store(&result, "SPRING_KAFKA_SSL_TRUST_STORE");
+        assert_eq!(trust_store.entries_count(), 1);
+        assert!(matches!(
+            trust_store.entry("kafka-trusted-cert-0"),
+            Some(KeyStoreEntry::Certificate(_))
+        ));
+
+        // Restarts replace the stores of the previous start.
+        let result = kafka_ssl_env_vars(&heroku_kafka_env(), &keystore_dir).unwrap();
+        assert_eq!(
+            read_store(&result, "SPRING_KAFKA_SSL_TRUST_STORE").entries_count(),
+            1
+        );
+
+        let key_store = read_store(&result, "SPRING_KAFKA_SSL_KEY_STORE");
+        let (alias, key_chain) = key_store.private_key_chain().unwrap();
+        assert_eq!(alias, "kafka-client");
+        assert_eq!(key_chain.chain().len(), 1);
+        assert_eq!(
+            key_chain.key(),
+            parse_private_key(CLIENT_CERT_KEY_PKCS8).unwrap()
+        );
+
+        assert_eq!(
+            result.get("SPRING_KAFKA_SSL_KEY_PASSWORD"),
+            result.get("SPRING_KAFKA_SSL_KEY_STORE_PASSWORD")
+        );
+        assert_ne!(
+            result.get("SPRING_KAFKA_SSL_TRUST_STORE_PASSWORD"),
+            result.get("SPRING_KAFKA_SSL_KEY_STORE_PASSWORD")
+        );
+    }
+
+    #[test]
+    fn key_stores_already_configured() {
+        let parent_dir = tempfile::tempdir().unwrap();
+
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("SPRING_KAFKA_SSL_TRUST_STORE_LOCATION"),
+            String::from("classpath:truststore.p12"),
+        );
+
+        let result = kafka_ssl_env_vars(&env, parent_dir.path()).unwrap();
+        assert!(!result.contains_key("SPRING_KAFKA_SSL_TRUST_STORE_LOCATION"));
+        assert!(result.contains_key("SPRING_KAFKA_SSL_KEY_STORE_LOCATION"));
+
+        env.insert(
+            String::from("SPRING_KAFKA_SSL_KEY_STORE_LOCATION"),
+            String::from("classpath:keystore.p12"),
+        );
+
+        let parent_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            kafka_ssl_env_vars(&env, &parent_dir.path().join("kafka")).unwrap(),
+            HashMap::new()
+        );
+        assert_eq!(std::fs::read_dir(parent_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn kafka_config_disabled() {
+        let parent_dir = tempfile::tempdir().unwrap();
+
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("DISABLE_SPRING_KAFKA_CONFIG"),
+            String::from("true"),
+        );
+
+        assert_eq!(kafka_env_vars(&env), HashMap::new());
+        assert_eq!(
+            kafka_ssl_env_vars(&env, parent_dir.path()).unwrap(),
+            HashMap::new()
+        );
+
+        env.insert(
+            String::from("DISABLE_SPRING_KAFKA_CONFIG"),
+            String::from("false"),
+        );
+
+        assert!(!kafka_env_vars(&env).is_empty());
+        assert!(
+            !kafka_ssl_env_vars(&env, parent_dir.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn invalid_certificates() {
+        let parent_dir = tempfile::tempdir().unwrap();
+
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("KAFKA_TRUSTED_CERT"),
+            String::from(CLIENT_CERT_KEY),
+        );
+
+        assert!(matches!(
+            kafka_ssl_env_vars(&env, parent_dir.path()),
+            Err(KafkaEnvVarError::MissingCertificate("KAFKA_TRUSTED_CERT"))
+        ));
+
+        let mut env = heroku_kafka_env();
+        env.insert(
+            String::from("KAFKA_CLIENT_CERT_KEY"),
+            String::from(CLIENT_CERT),
+        );
+
+        assert!(matches!(
+            kafka_ssl_env_vars(&env, parent_dir.path()),
+            Err(KafkaEnvVarError::UnsupportedPrivateKey(tag)) if tag == "CERTIFICATE"
+        ));
+    }
+}
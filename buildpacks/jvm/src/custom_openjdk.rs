@@ -0,0 +1,158 @@
+use crate::layers::openjdk::OpenJdkTarball;
+use buildpacks_jvm_shared::system_properties::{ReadSystemPropertiesError, read_system_properties};
+use libcnb::Env;
+use libherokubuildpack::inventory::checksum::{Checksum, ChecksumParseError};
+use sha2::Sha256;
+use std::path::Path;
+
+pub(crate) const JAVA_RUNTIME_URL_ENV_VAR_NAME: &str = "JAVA_RUNTIME_URL";
+pub(crate) const JAVA_RUNTIME_SHA256_ENV_VAR_NAME: &str = "JAVA_RUNTIME_SHA256";
+const JAVA_RUNTIME_URL_PROPERTY_NAME: &str = "java.runtime.url";
+const JAVA_RUNTIME_SHA256_PROPERTY_NAME: &str = "java.runtime.sha256";
+
+/// Reads the configuration for a user provided OpenJDK tarball that bypasses the inventory.
+///
+/// The tarball can be configured with the `java.runtime.url` and `java.runtime.sha256` properties
+/// in `system.properties` or the `JAVA_RUNTIME_URL` and `JAVA_RUNTIME_SHA256` environment
+/// variables. The environment variables take precedence.
+pub(crate) fn read_custom_openjdk_tarball(
+    app_dir: &Path,
+    env: &Env,
+) -> Result<Option<OpenJdkTarball>, CustomOpenJdkTarballError> {
+    let properties =
+        read_system_properties(app_dir).map_err(CustomOpenJdkTarballError::ReadSystemProperties)?;
+
+    let url = env
+        .get_string_lossy(JAVA_RUNTIME_URL_ENV_VAR_NAME)
+        .or_else(|| properties.get(JAVA_RUNTIME_URL_PROPERTY_NAME).cloned());
+
+    let sha256 = env
+        .get_string_lossy(JAVA_RUNTIME_SHA256_ENV_VAR_NAME)
+        .or_else(|| properties.get(JAVA_RUNTIME_SHA256_PROPERTY_NAME).cloned());
+
+    match (url, sha256) {
+        (None, None) => Ok(None),
+        (Some(url), Some(sha256)) => format!("sha256:{}", sha256.trim().to_lowercase())
+            .parse::<Checksum<Sha256>>()
+            .map_err(CustomOpenJdkTarballError::InvalidChecksum)
+            .map(|checksum| {
+                Some(OpenJdkTarball {
+                    url: String::from(url.trim()),
+                    checksum,
+                    from_inventory: false,
+                    version: None,
+                    distribution: None,
+                })
+            }),
+        (Some(_), None) => Err(CustomOpenJdkTarballError::MissingChecksum),
+        (None, Some(_)) => Err(CustomOpenJdkTarballError::MissingUrl),
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum CustomOpenJdkTarballError {
+    ReadSystemProperties(ReadSystemPropertiesError),
+    MissingChecksum,
+    MissingUrl,
+    InvalidChecksum(ChecksumParseError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SHA256: &str = "7cafe7cc263ba63115fb801049efb9bfae762c46f252a451ba05734224cb42da";
+
+    #[test]
+    fn no_custom_tarball() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.version=21",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_custom_openjdk_tarball(app_dir.path(), &Env::new()),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn custom_tarball_from_system_properties() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            format!(
+                "java.runtime.url=https://example.com/jdk.tar.gz\njava.runtime.sha256={}\n",
+                SHA256.to_uppercase()
+            ),
+        )
+        .unwrap();
+
+        let tarball = read_custom_openjdk_tarball(app_dir.path(), &Env::new())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(tarball.url, "https://example.com/jdk.tar.gz");
+        assert_eq!(hex::encode(tarball.checksum.value), SHA256);
+        assert!(!tarball.from_inventory);
+    }
+
+    #[test]
+    fn custom_tarball_env_vars_take_precedence() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.url=https://example.com/jdk.tar.gz\njava.runtime.sha256=abc\n",
+        )
+        .unwrap();
+
+        let mut env = Env::new();
+        env.insert(JAVA_RUNTIME_URL_ENV_VAR_NAME, "file:///opt/jdk.tar.gz");
+        env.insert(JAVA_RUNTIME_SHA256_ENV_VAR_NAME, SHA256);
+
+        let tarball = read_custom_openjdk_tarball(app_dir.path(), &env)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(tarball.url, "file:///opt/jdk.tar.gz");
+        assert_eq!(hex::encode(tarball.checksum.value), SHA256);
+    }
+
+    #[test]
+    fn incomplete_custom_tarball_configuration() {
+        let app_dir = tempdir().unwrap();
+
+        let mut env = Env::new();
+        env.insert(
+            JAVA_RUNTIME_URL_ENV_VAR_NAME,
+            "https://example.com/jdk.tar.gz",
+        );
+
+        assert!(matches!(
+            read_custom_openjdk_tarball(app_dir.path(), &env),
+            Err(CustomOpenJdkTarballError::MissingChecksum)
+        ));
+
+        let mut env = Env::new();
+        env.insert(JAVA_RUNTIME_SHA256_ENV_VAR_NAME, SHA256);
+
+        assert!(matches!(
+            read_custom_openjdk_tarball(app_dir.path(), &env),
+            Err(CustomOpenJdkTarballError::MissingUrl)
+        ));
+
+        env.insert(
+            JAVA_RUNTIME_URL_ENV_VAR_NAME,
+            "https://example.com/jdk.tar.gz",
+        );
+        env.insert(JAVA_RUNTIME_SHA256_ENV_VAR_NAME, "not-a-checksum");
+
+        assert!(matches!(
+            read_custom_openjdk_tarball(app_dir.path(), &env),
+            Err(CustomOpenJdkTarballError::InvalidChecksum(_))
+        ));
+    }
+}
@@ -1,8 +1,13 @@
 use crate::OpenJdkBuildpackError;
+use crate::agents::{JavaAgentsConfigError, KNOWN_JAVA_AGENTS};
+use crate::custom_openjdk::{
+    CustomOpenJdkTarballError, JAVA_RUNTIME_SHA256_ENV_VAR_NAME, JAVA_RUNTIME_URL_ENV_VAR_NAME,
+};
+use crate::mirror::OPENJDK_MIRROR_URL_ENV_VAR_NAME;
 use crate::openjdk_artifact::{
     HerokuOpenJdkVersionRequirement, OpenJdkArtifactRequirementParseError,
 };
-use crate::version_resolver::VersionResolveError;
+use crate::version_resolver::{OpenJdkArtifactRequirementSource, VersionResolveError};
 use buildpacks_jvm_shared as shared;
 use indoc::formatdoc;
 
@@ -16,8 +21,15 @@ pub(crate) fn on_error_jvm_buildpack(error: OpenJdkBuildpackError) {
                 error,
             );
         }
-        OpenJdkBuildpackError::ReadSystemPropertiesError(
-            shared::system_properties::ReadSystemPropertiesError::ParseError(error),
+        OpenJdkBuildpackError::ResolveVersionError(
+            VersionResolveError::ReadSystemPropertiesError(
+                shared::system_properties::ReadSystemPropertiesError::ParseError(error),
+            ),
+        )
+        | OpenJdkBuildpackError::CustomOpenJdkTarballError(
+            CustomOpenJdkTarballError::ReadSystemProperties(
+                shared::system_properties::ReadSystemPropertiesError::ParseError(error),
+            ),
         ) => shared::output::print_error(
             "Invalid system.properties file",
             formatdoc! {"
@@ -27,8 +39,15 @@ pub(crate) fn on_error_jvm_buildpack(error: OpenJdkBuildpackError) {
                 Details: {error}
             ", error = error },
         ),
-        OpenJdkBuildpackError::ReadSystemPropertiesError(
-            shared::system_properties::ReadSystemPropertiesError::IoError(error),
+        OpenJdkBuildpackError::ResolveVersionError(
+            VersionResolveError::ReadSystemPropertiesError(
+                shared::system_properties::ReadSystemPropertiesError::IoError(error),
+            ),
+        )
+        | OpenJdkBuildpackError::CustomOpenJdkTarballError(
+            CustomOpenJdkTarballError::ReadSystemProperties(
+                shared::system_properties::ReadSystemPropertiesError::IoError(error),
+            ),
         ) => shared::log::log_please_try_again_error(
             "Unexpected IO error",
             "Could not read your application's system.properties file due to an unexpected I/O error.",
@@ -83,26 +102,67 @@ pub(crate) fn on_error_jvm_buildpack(error: OpenJdkBuildpackError) {
                 error,
             );
         }
-        OpenJdkBuildpackError::UnsupportedOpenJdkVersion(artifact_requirement) => {
+        OpenJdkBuildpackError::InvalidOpenJdkMirrorUrl(mirror_url, error) => {
+            shared::output::print_error(
+                "Invalid OpenJDK mirror URL",
+                formatdoc! {"
+                    The OpenJDK mirror URL configured with {OPENJDK_MIRROR_URL_ENV_VAR_NAME} is not a valid URL.
+                    Please use a HTTP(S) URL or a file:// URL that points to a directory.
+
+                    Value: {mirror_url}
+                    Details: {error}
+                "},
+            );
+        }
+        OpenJdkBuildpackError::InvalidOpenJdkDownloadUrl(error) => {
+            shared::log::log_please_try_again_error(
+                "Invalid OpenJDK download URL",
+                "Could not construct the download URL for the OpenJDK distribution.",
+                error,
+            );
+        }
+        OpenJdkBuildpackError::UnsupportedOpenJdkVersion(
+            OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+            | OpenJdkArtifactRequirementSource::DefaultVersionFunctions,
+            artifact_requirement,
+        ) => shared::output::print_error(
+            "Unsupported OpenJDK version",
+            formatdoc! {"
+                The default OpenJDK version of this buildpack ({version}, {distribution}) is not
+                available for your platform. Please specify a supported OpenJDK version with
+                java.runtime.version in your system.properties file.
+            ", version = artifact_requirement.version, distribution = artifact_requirement.distribution },
+        ),
+        OpenJdkBuildpackError::UnsupportedOpenJdkVersion(source, artifact_requirement) => {
+            let distribution = artifact_requirement.distribution;
+
             match artifact_requirement.version {
                 HerokuOpenJdkVersionRequirement::Major(major_version) => {
                     shared::output::print_error(
                         "Unsupported OpenJDK version",
                         formatdoc! {"
-                        The OpenJDK major version {major_version} you specified in your system.properties file is not supported.
-                        Please specify a supported major version in your system.properties file.
-                    ", major_version = major_version },
+                        The OpenJDK major version {major_version} ({distribution}) you specified in your {source} file is not supported.
+                        Please specify a supported major version in your {source} file.
+                    ", major_version = major_version, distribution = distribution, source = source },
                     );
                 }
                 HerokuOpenJdkVersionRequirement::Specific(version) => shared::output::print_error(
                     "Unsupported OpenJDK version",
                     formatdoc! {"
-                        The OpenJDK version {version} you specified in your system.properties file is not supported.
-                        Please specify a supported version in your system.properties file.
+                        The OpenJDK version {version} ({distribution}) you specified in your {source} file is not supported.
+                        Please specify a supported version in your {source} file.
 
-                        We recommend specifying only the major version in system.properties.
+                        We recommend specifying only the major version in {source}.
                         This will cause the buildpack to always install the latest version of the chosen major version.
-                    ", version = version },
+                    ", version = version, distribution = distribution, source = source },
+                ),
+                HerokuOpenJdkVersionRequirement::Range(range) => shared::output::print_error(
+                    "Unsupported OpenJDK version",
+                    formatdoc! {"
+                        No supported OpenJDK version ({distribution}) matches the version range {range}
+                        you specified in your {source} file. Please adjust the version range in your
+                        {source} file.
+                    ", range = range, distribution = distribution, source = source },
                 ),
             }
         }
@@ -131,15 +191,18 @@ pub(crate) fn on_error_jvm_buildpack(error: OpenJdkBuildpackError) {
         }
         OpenJdkBuildpackError::ResolveVersionError(
             VersionResolveError::OpenJdkArtifactRequirementParseError(
+                source,
                 OpenJdkArtifactRequirementParseError::UnknownDistribution(distribution),
             ),
         ) => shared::output::print_error(
             format!("Unsupported distribution: {distribution}"),
             formatdoc! {"
-                    Please check your system.properties file to ensure the java.runtime.version
-                    string does not contain an unsupported distribution prefix.
+                    Please check your {source} file to ensure the OpenJDK version string does
+                    not contain an unsupported distribution. The only supported distribution
+                    is zulu. Other distributions, such as GraalVM, can be installed from a
+                    tarball with the java.runtime.url and java.runtime.sha256 properties.
 
-                    You can also remove the system.properties file from your application to install
+                    You can also remove the {source} file from your application to install
                     the default OpenJDK version.
 
                     Thanks,
@@ -148,31 +211,196 @@ pub(crate) fn on_error_jvm_buildpack(error: OpenJdkBuildpackError) {
         ),
         OpenJdkBuildpackError::ResolveVersionError(
             VersionResolveError::OpenJdkArtifactRequirementParseError(
-                OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError(_),
+                source,
+                OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError(parse_error),
             ),
         ) => {
             shared::output::print_error(
                 "Invalid OpenJDK version selector",
                 formatdoc! {"
-            The OpenJDK version selector you specified in your system.properties file is invalid.
-            Please specify a valid version selector in your system.properties file.
+            The OpenJDK version selector you specified in your {source} file is invalid.
+            Please specify a valid version selector in your {source} file.
 
-            Details: {error:?}
-        ", error = error },
+            Details: {parse_error:?}
+        " },
             );
         }
-        OpenJdkBuildpackError::ResolveVersionError(
-            VersionResolveError::ReadSystemPropertiesError(error),
-        ) => {
-            shared::output::print_error(
-                "Invalid system.properties file",
+        OpenJdkBuildpackError::ResolveVersionError(VersionResolveError::ReadVersionFileError(
+            source,
+            error,
+        )) => shared::log::log_please_try_again_error(
+            "Unexpected IO error",
+            format!(
+                "Could not read your application's {source} file due to an unexpected I/O error."
+            ),
+            error,
+        ),
+        OpenJdkBuildpackError::CustomOpenJdkTarballError(
+            CustomOpenJdkTarballError::MissingChecksum | CustomOpenJdkTarballError::MissingUrl,
+        ) => shared::output::print_error(
+            "Incomplete custom OpenJDK tarball configuration",
+            formatdoc! {"
+                A custom OpenJDK tarball requires both a URL and a SHA-256 checksum. Please set
+                both java.runtime.url and java.runtime.sha256 in your system.properties file or
+                both the {JAVA_RUNTIME_URL_ENV_VAR_NAME} and {JAVA_RUNTIME_SHA256_ENV_VAR_NAME} environment variables.
+            "},
+        ),
+        OpenJdkBuildpackError::CustomOpenJdkTarballError(
+            CustomOpenJdkTarballError::InvalidChecksum(error),
+        ) => shared::output::print_error(
+            "Invalid custom OpenJDK tarball checksum",
+            formatdoc! {"
+                The checksum configured for your custom OpenJDK tarball is not a valid SHA-256
+                checksum. Please specify the checksum as 64 hexadecimal characters.
+
+                Details: {error}
+            "},
+        ),
+        OpenJdkBuildpackError::ReadRuntimeImageConfigError(error) => {
+            shared::log::log_runtime_image_config_error(error);
+        }
+        OpenJdkBuildpackError::CannotCreateRuntimeImage(error) => {
+            shared::log::log_runtime_image_error(error);
+        }
+        OpenJdkBuildpackError::CannotCreateOpenJdkSbom(error) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not read the release file of the OpenJDK installation to create its SBOM.",
+                error,
+            );
+        }
+        OpenJdkBuildpackError::ReadJavaAgentsConfigError(error) => {
+            on_java_agents_config_error(error);
+        }
+        OpenJdkBuildpackError::InvalidJavaAgentUrl(name, error) => shared::output::print_error(
+            "Invalid Java agent URL",
+            formatdoc! {"
+                The URL of the Java agent '{name}' is not a valid URL. Please use a HTTP(S) URL
+                or a file:// URL.
+
+                Details: {error}
+            "},
+        ),
+        OpenJdkBuildpackError::JavaAgentDownloadError(name, error) => {
+            shared::log::log_please_try_again_error(
+                "Java agent download failed",
+                format!("Could not download the Java agent '{name}'."),
+                error,
+            );
+        }
+        OpenJdkBuildpackError::JavaAgentChecksumError {
+            name,
+            expected,
+            actual,
+        } => {
+            shared::log::log_please_try_again(
+                "Corrupted Java agent download",
                 formatdoc! {"
-            The contents of your system.properties file cannot be parsed. Please use a valid
-            system.properties file and try again.
+                The validation of the downloaded Java agent '{name}' failed due to a checksum mismatch.
 
-            Details: {error:?}
-        ", error = error },
+                Expected: {expected}
+                Actual: {actual}
+            ", expected = hex::encode(expected), actual = hex::encode(actual) },
             );
         }
+        OpenJdkBuildpackError::CannotReadJavaAgent(error) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not read downloaded Java agent file.",
+                error,
+            );
+        }
+    }
+}
+
+fn on_java_agents_config_error(error: JavaAgentsConfigError) {
+    match error {
+        JavaAgentsConfigError::ReadSystemProperties(
+            shared::system_properties::ReadSystemPropertiesError::ParseError(error),
+        ) => shared::output::print_error(
+            "Invalid system.properties file",
+            formatdoc! {"
+                Could not parse your application's system.properties file. Please ensure that your
+                system.properties file is a valid Java properties file and try again.
+
+                Details: {error}
+            "},
+        ),
+        JavaAgentsConfigError::ReadSystemProperties(
+            shared::system_properties::ReadSystemPropertiesError::IoError(error),
+        ) => shared::log::log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not read your application's system.properties file due to an unexpected I/O error.",
+            error,
+        ),
+        JavaAgentsConfigError::ReadOpenTelemetryAgentEnabled(error) => {
+            shared::log::log_read_boolean_setting_error(error);
+        }
+        JavaAgentsConfigError::ReadProjectToml(error) => shared::log::log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not read your application's project.toml file due to an unexpected I/O error.",
+            error,
+        ),
+        JavaAgentsConfigError::ParseProjectToml(error) => shared::output::print_error(
+            "Invalid Java agent configuration",
+            formatdoc! {"
+                Could not parse the [[com.heroku.buildpacks.jvm.agents]] array of tables in your
+                application's project.toml file. Each agent supports the keys name, url, sha256
+                and options.
+
+                Details: {error}
+            "},
+        ),
+        JavaAgentsConfigError::InvalidName(name) => shared::output::print_error(
+            "Invalid Java agent name",
+            formatdoc! {"
+                The Java agent name '{name}' is invalid. Java agent names may only contain
+                lowercase letters, digits and dashes.
+            "},
+        ),
+        JavaAgentsConfigError::UnknownAgent(name) => shared::output::print_error(
+            "Unknown Java agent",
+            formatdoc! {"
+                The Java agent '{name}' is not known to this buildpack. Please configure the
+                agent's URL and SHA-256 checksum or use one of the known agents, which only
+                require a SHA-256 checksum: {known_agents}
+            ", known_agents = KNOWN_JAVA_AGENTS
+            .iter()
+            .map(|agent| agent.name)
+            .collect::<Vec<_>>()
+            .join(", ") },
+        ),
+        JavaAgentsConfigError::MissingChecksum(name) => shared::output::print_error(
+            "Incomplete Java agent configuration",
+            formatdoc! {"
+                The Java agent '{name}' requires a SHA-256 checksum. Please set
+                java.agent.{name}.sha256 in your system.properties file or sha256 in your
+                project.toml file. The checksum is used to verify the downloaded JAR file.
+            "},
+        ),
+        JavaAgentsConfigError::MissingUrl(name) => shared::output::print_error(
+            "Incomplete Java agent configuration",
+            formatdoc! {"
+                The Java agent '{name}' requires both a URL and a SHA-256 checksum. Please set
+                both java.agent.{name}.url and java.agent.{name}.sha256 in your system.properties
+                file or both url and sha256 in your project.toml file.
+            "},
+        ),
+        JavaAgentsConfigError::InvalidChecksum(name, error) => shared::output::print_error(
+            "Invalid Java agent checksum",
+            formatdoc! {"
+                The checksum configured for the Java agent '{name}' is not a valid SHA-256
+                checksum. Please specify the checksum as 64 hexadecimal characters.
+
+                Details: {error}
+            "},
+        ),
+        JavaAgentsConfigError::DuplicateAgent(name) => shared::output::print_error(
+            "Duplicate Java agent",
+            formatdoc! {"
+                The Java agent '{name}' is configured more than once. Please configure each
+                agent only once, either in system.properties or in project.toml.
+            "},
+        ),
     }
 }
@@ -0,0 +1,151 @@
+use crate::agents::{JavaAgent, OPENTELEMETRY_AGENT_NAME};
+use crate::mirror::fetch_file;
+use crate::util::digest;
+use crate::{
+    JAVA_TOOL_OPTIONS_ENV_VAR_DELIMITER, JAVA_TOOL_OPTIONS_ENV_VAR_NAME, OpenJdkBuildpack,
+    OpenJdkBuildpackError,
+};
+use buildpacks_jvm_shared::output;
+use buildpacks_jvm_shared::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use libcnb::additional_buildpack_binary_path;
+use libcnb::build::BuildContext;
+use libcnb::data::layer::LayerName;
+use libcnb::layer::{
+    CachedLayerDefinition, EmptyLayerCause, InvalidMetadataAction, LayerState, RestoredLayerAction,
+};
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::Path;
+use url::Url;
+
+/// Installs the given Java agent into its own launch layer and adds it to `JAVA_TOOL_OPTIONS`.
+pub(crate) fn handle_agent_layer(
+    context: &BuildContext<OpenJdkBuildpack>,
+    agent: &JavaAgent,
+) -> libcnb::Result<(), OpenJdkBuildpackError> {
+    let layer_metadata = AgentLayerMetadata {
+        url: agent.url.clone(),
+        sha256: hex::encode(&agent.checksum.value),
+    };
+
+    let layer_ref = context.cached_layer(
+        format!("agent-{}", agent.name)
+            .parse::<LayerName>()
+            .expect("Java agent names should be valid layer names, they are validated when reading the configuration"),
+        CachedLayerDefinition {
+            build: false,
+            launch: true,
+            invalid_metadata_action: &|_| InvalidMetadataAction::DeleteLayer,
+            restored_layer_action: &|metadata: &AgentLayerMetadata, _| {
+                if metadata == &layer_metadata {
+                    (RestoredLayerAction::KeepLayer, AgentLayerCause::Valid)
+                } else {
+                    (RestoredLayerAction::DeleteLayer, AgentLayerCause::Changed)
+                }
+            },
+        },
+    )?;
+
+    let agent_jar_path = layer_ref.path().join(format!("{}.jar", agent.name));
+
+    match layer_ref.state {
+        LayerState::Restored { .. } => {
+            output::print_subsection(BuildpackOutputText::new(vec![
+                BuildpackOutputTextSection::regular("Using cached "),
+                BuildpackOutputTextSection::value(&agent.name),
+                BuildpackOutputTextSection::regular(" agent from previous build"),
+            ]));
+        }
+        LayerState::Empty { ref cause } => {
+            if let EmptyLayerCause::RestoredLayerAction {
+                cause: AgentLayerCause::Changed,
+            } = cause
+            {
+                output::print_subsection(BuildpackOutputText::new(vec![
+                    BuildpackOutputTextSection::regular("Clearing "),
+                    BuildpackOutputTextSection::value(&agent.name),
+                    BuildpackOutputTextSection::regular(" agent cache (configuration changed)"),
+                ]));
+            }
+
+            output::track_subsection_timing(|| {
+                output::print_subsection(BuildpackOutputText::new(vec![
+                    BuildpackOutputTextSection::regular("Downloading "),
+                    BuildpackOutputTextSection::value(&agent.name),
+                    BuildpackOutputTextSection::regular(" agent from "),
+                    BuildpackOutputTextSection::value(&agent.url),
+                ]));
+
+                download_agent(agent, &agent_jar_path)
+            })?;
+
+            layer_ref.write_metadata(layer_metadata.clone())?;
+        }
+    }
+
+    let java_agent_option = match &agent.options {
+        Some(options) => format!("-javaagent:{}={options}", agent_jar_path.to_string_lossy()),
+        None => format!("-javaagent:{}", agent_jar_path.to_string_lossy()),
+    };
+
+    layer_ref.write_env(
+        LayerEnv::new()
+            .chainable_insert(
+                Scope::Launch,
+                ModificationBehavior::Delimiter,
+                JAVA_TOOL_OPTIONS_ENV_VAR_NAME,
+                JAVA_TOOL_OPTIONS_ENV_VAR_DELIMITER,
+            )
+            .chainable_insert(
+                Scope::Launch,
+                ModificationBehavior::Prepend,
+                JAVA_TOOL_OPTIONS_ENV_VAR_NAME,
+                java_agent_option,
+            ),
+    )?;
+
+    // Derives the OpenTelemetry service name and resource attributes from the dyno metadata.
+    if agent.name == OPENTELEMETRY_AGENT_NAME {
+        layer_ref.write_exec_d_programs([(
+            "heroku_otel_env_vars",
+            additional_buildpack_binary_path!("heroku_otel_env_vars"),
+        )])?;
+    }
+
+    Ok(())
+}
+
+fn download_agent(agent: &JavaAgent, destination: &Path) -> Result<(), OpenJdkBuildpackError> {
+    let url = Url::parse(&agent.url)
+        .map_err(|error| OpenJdkBuildpackError::InvalidJavaAgentUrl(agent.name.clone(), error))?;
+
+    fetch_file(&url, destination).map_err(|error| {
+        OpenJdkBuildpackError::JavaAgentDownloadError(agent.name.clone(), error)
+    })?;
+
+    let actual_checksum = std::fs::File::open(destination)
+        .and_then(digest::<Sha256>)
+        .map_err(OpenJdkBuildpackError::CannotReadJavaAgent)?;
+
+    if actual_checksum.as_slice() == agent.checksum.value {
+        Ok(())
+    } else {
+        Err(OpenJdkBuildpackError::JavaAgentChecksumError {
+            name: agent.name.clone(),
+            expected: agent.checksum.value.clone(),
+            actual: actual_checksum.to_vec(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct AgentLayerMetadata {
+    url: String,
+    sha256: String,
+}
+
+pub(crate) enum AgentLayerCause {
+    Valid,
+    Changed,
+}
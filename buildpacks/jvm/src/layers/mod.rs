@@ -1,2 +1,3 @@
+pub(crate) mod agent;
 pub(crate) mod openjdk;
 pub(crate) mod runtime;
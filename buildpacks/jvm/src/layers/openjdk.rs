@@ -1,5 +1,7 @@
-use crate::openjdk_artifact::OpenJdkArtifactMetadata;
+use crate::mirror::{OPENJDK_MIRROR_URL_ENV_VAR_NAME, fetch_file, mirror_artifact_url, redact_url};
+use crate::openjdk_artifact::{OpenJdkArtifactMetadata, OpenJdkDistribution};
 use crate::openjdk_version::OpenJdkVersion;
+use crate::sbom::openjdk_sboms;
 use crate::util::digest;
 use crate::{
     JAVA_TOOL_OPTIONS_ENV_VAR_DELIMITER, JAVA_TOOL_OPTIONS_ENV_VAR_NAME, JDK_OVERLAY_DIR_NAME,
@@ -7,33 +9,50 @@ use crate::{
 };
 use buildpacks_jvm_shared::output;
 use buildpacks_jvm_shared::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use buildpacks_jvm_shared::runtime_image::RuntimeImageType;
 use fs_extra::dir::CopyOptions;
-use libcnb::additional_buildpack_binary_path;
 use libcnb::build::BuildContext;
 use libcnb::data::layer_name;
 use libcnb::layer::{
     CachedLayerDefinition, EmptyLayerCause, InvalidMetadataAction, LayerState, RestoredLayerAction,
 };
 use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+use libcnb::{Platform, additional_buildpack_binary_path};
 use libherokubuildpack::inventory::artifact::Artifact;
+use libherokubuildpack::inventory::checksum::Checksum;
 use serde::Deserialize;
 use serde::Serialize;
 use sha2::Sha256;
 use std::path::PathBuf;
 use tempfile::tempdir;
+use url::Url;
 
 #[allow(clippy::too_many_lines)]
 pub(crate) fn handle_openjdk_layer(
     context: &BuildContext<OpenJdkBuildpack>,
-    artifact: &Artifact<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>,
-) -> libcnb::Result<(), OpenJdkBuildpackError> {
+    tarball: &OpenJdkTarball,
+    runtime_image_type: RuntimeImageType,
+) -> libcnb::Result<PathBuf, OpenJdkBuildpackError> {
     output::print_section("OpenJDK Installation");
 
+    let mirror_url = context
+        .platform
+        .env()
+        .get_string_lossy(OPENJDK_MIRROR_URL_ENV_VAR_NAME)
+        .map(|mirror_url| {
+            Url::parse(&mirror_url)
+                .map_err(|error| OpenJdkBuildpackError::InvalidOpenJdkMirrorUrl(mirror_url, error))
+        })
+        .transpose()?;
+
+    let tarball_checksum = hex::encode(&tarball.checksum.value);
+
     let layer_ref = context.cached_layer(
         layer_name!("openjdk"),
         CachedLayerDefinition {
             build: true,
-            launch: true,
+            // When a separate runtime is used at launch, the full JDK is only needed for the build.
+            launch: runtime_image_type == RuntimeImageType::Jdk,
             invalid_metadata_action: &|_| InvalidMetadataAction::DeleteLayer,
             restored_layer_action: &|metadata: &OpenJdkLayerMetadata, _| {
                 if context.app_dir.join(JDK_OVERLAY_DIR_NAME).exists()
@@ -46,7 +65,14 @@ pub(crate) fn handle_openjdk_layer(
                         RestoredLayerAction::DeleteLayer,
                         OpenJdkLayerCause::OverlayUsed,
                     )
-                } else if artifact.url != metadata.source_tarball_url {
+                } else if metadata
+                    .source_tarball_checksum
+                    .as_ref()
+                    // Layers from previous buildpack versions only recorded the tarball URL.
+                    .map_or(tarball.url != metadata.source_tarball_url, |checksum| {
+                        *checksum != tarball_checksum
+                    })
+                {
                     (
                         RestoredLayerAction::DeleteLayer,
                         OpenJdkLayerCause::VersionChanged,
@@ -82,11 +108,29 @@ pub(crate) fn handle_openjdk_layer(
             output::track_subsection_timing(|| {
                 output::print_subsection("Downloading and unpacking OpenJDK distribution");
 
+                // The layer metadata always refers to the inventory URL, switching mirrors does
+                // not invalidate the cached OpenJDK installation. Custom tarballs are never
+                // mirrored, their URL is used as-is.
+                let mirror_url = mirror_url.as_ref().filter(|_| tarball.from_inventory);
+
+                let download_url = match mirror_url {
+                    Some(mirror_url) => mirror_artifact_url(mirror_url, &tarball.url),
+                    None => Url::parse(&tarball.url),
+                }
+                .map_err(OpenJdkBuildpackError::InvalidOpenJdkDownloadUrl)?;
+
+                if mirror_url.is_some() {
+                    output::print_subsection(BuildpackOutputText::new(vec![
+                        BuildpackOutputTextSection::regular("Using mirror URL "),
+                        BuildpackOutputTextSection::value(redact_url(&download_url).as_str()),
+                    ]));
+                }
+
                 let temp_dir =
                     tempdir().map_err(OpenJdkBuildpackError::CannotCreateOpenJdkTempDir)?;
                 let path = temp_dir.path().join("openjdk.tar.gz");
 
-                libherokubuildpack::download::download_file(&artifact.url, &path)
+                fetch_file(&download_url, &path)
                     .map_err(OpenJdkBuildpackError::OpenJdkDownloadError)?;
 
                 std::fs::File::open(&path)
@@ -96,11 +140,11 @@ pub(crate) fn handle_openjdk_layer(
                             .map_err(OpenJdkBuildpackError::CannotReadOpenJdkTarball)
                     })
                     .and_then(|downloaded_file_digest| {
-                        if downloaded_file_digest.as_slice() == artifact.checksum.value {
+                        if downloaded_file_digest.as_slice() == tarball.checksum.value {
                             Ok(())
                         } else {
                             Err(OpenJdkBuildpackError::OpenJdkTarballChecksumError {
-                                expected: artifact.checksum.value.clone(),
+                                expected: tarball.checksum.value.clone(),
                                 actual: downloaded_file_digest.to_vec(),
                             })
                         }
@@ -110,6 +154,9 @@ pub(crate) fn handle_openjdk_layer(
                     .map_err(OpenJdkBuildpackError::CannotReadOpenJdkTarball)
                     .and_then(|mut file| {
                         libherokubuildpack::tar::decompress_tarball(&mut file, layer_ref.path())
+                            // Upstream OpenJDK tarballs usually contain a single top-level directory
+                            // (i.e. `jdk-21.0.5+11`) while the inventory tarballs don't.
+                            .and_then(|()| util::flatten_single_directory(layer_ref.path()))
                             .map_err(OpenJdkBuildpackError::CannotDecompressOpenJdkTarball)
                     })
             })?;
@@ -203,7 +250,8 @@ pub(crate) fn handle_openjdk_layer(
             }
 
             layer_ref.write_metadata(OpenJdkLayerMetadata {
-                source_tarball_url: artifact.url.clone(),
+                source_tarball_url: tarball.url.clone(),
+                source_tarball_checksum: Some(tarball_checksum),
                 jdk_overlay_applied,
             })?;
 
@@ -236,13 +284,43 @@ pub(crate) fn handle_openjdk_layer(
         }
     }
 
-    Ok(())
+    layer_ref.write_sboms(
+        &openjdk_sboms(tarball, &layer_ref.path())
+            .map_err(OpenJdkBuildpackError::CannotCreateOpenJdkSbom)?,
+    )?;
+
+    Ok(layer_ref.path())
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct OpenJdkLayerMetadata {
     jdk_overlay_applied: bool,
     source_tarball_url: String,
+    #[serde(default)]
+    source_tarball_checksum: Option<String>,
+}
+
+/// An OpenJDK tarball to install, either resolved from the inventory or provided by the user.
+#[derive(Debug, Clone)]
+pub(crate) struct OpenJdkTarball {
+    pub(crate) url: String,
+    pub(crate) checksum: Checksum<Sha256>,
+    pub(crate) from_inventory: bool,
+    /// Version and distribution are only known for tarballs from the inventory.
+    pub(crate) version: Option<OpenJdkVersion>,
+    pub(crate) distribution: Option<OpenJdkDistribution>,
+}
+
+impl From<&Artifact<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>> for OpenJdkTarball {
+    fn from(artifact: &Artifact<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>) -> Self {
+        OpenJdkTarball {
+            url: artifact.url.clone(),
+            checksum: artifact.checksum.clone(),
+            from_inventory: true,
+            version: Some(artifact.version.clone()),
+            distribution: Some(artifact.metadata.distribution),
+        }
+    }
 }
 
 pub(crate) enum OpenJdkLayerCause {
@@ -1,11 +1,23 @@
-use crate::{OpenJdkBuildpack, OpenJdkBuildpackError};
+use crate::layers::openjdk::OpenJdkTarball;
+use crate::sbom::openjdk_sboms;
+use crate::{
+    JAVA_TOOL_OPTIONS_ENV_VAR_DELIMITER, JAVA_TOOL_OPTIONS_ENV_VAR_NAME, OpenJdkBuildpack,
+    OpenJdkBuildpackError,
+};
+use buildpacks_jvm_shared::output;
+use buildpacks_jvm_shared::runtime_image::{RuntimeImageType, create_jre_image};
 use libcnb::additional_buildpack_binary_path;
 use libcnb::build::BuildContext;
 use libcnb::data::layer_name;
 use libcnb::layer::UncachedLayerDefinition;
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+use std::path::Path;
 
 pub(crate) fn handle_runtime_layer(
     context: &BuildContext<OpenJdkBuildpack>,
+    runtime_image_type: RuntimeImageType,
+    openjdk_tarball: &OpenJdkTarball,
+    openjdk_layer_path: &Path,
 ) -> libcnb::Result<(), OpenJdkBuildpackError> {
     let layer_ref = context.uncached_layer(
         layer_name!("runtime"),
@@ -15,8 +27,81 @@ pub(crate) fn handle_runtime_layer(
         },
     )?;
 
-    layer_ref.write_exec_d_programs([(
+    match runtime_image_type {
+        // The OpenJDK layer is available at launch and provides everything that is needed.
+        RuntimeImageType::Jdk => {}
+        RuntimeImageType::Jre => {
+            output::print_section("Creating JRE for launch");
+
+            output::track_subsection_timing(|| {
+                output::print_subsection("Creating JRE from the installed OpenJDK");
+                create_jre_image(openjdk_layer_path, &layer_ref.path())
+                    .map_err(OpenJdkBuildpackError::CannotCreateRuntimeImage)
+            })?;
+
+            layer_ref.write_sboms(
+                &openjdk_sboms(openjdk_tarball, &layer_ref.path())
+                    .map_err(OpenJdkBuildpackError::CannotCreateOpenJdkSbom)?,
+            )?;
+
+            layer_ref.write_env(launch_env().chainable_insert(
+                Scope::Launch,
+                ModificationBehavior::Override,
+                "JAVA_HOME",
+                layer_ref.path(),
+            ))?;
+        }
+        // The runtime is created by the build tool buildpack once the application has been built.
+        // JAVA_HOME for launch is set by that buildpack.
+        RuntimeImageType::Jlink => {
+            output::print_section("Creating jlink runtime for launch");
+            output::print_subsection(
+                "Skipping (the runtime is created after the application has been built)",
+            );
+
+            layer_ref.write_env(launch_env())?;
+        }
+        // No Java runtime is needed to run a native executable. The executable is installed by the
+        // build tool buildpack.
+        RuntimeImageType::Native => {
+            output::print_section("Creating runtime for launch");
+            output::print_subsection(
+                "Skipping (the application is compiled to a native executable)",
+            );
+
+            layer_ref.write_env(launch_env())?;
+        }
+    }
+
+    let mut exec_d_programs = vec![(
         "heroku_rewrite_env_vars",
         additional_buildpack_binary_path!("heroku_rewrite_env_vars"),
-    )])
+    )];
+
+    // The OpenJDK layer provides this program when it is available at launch.
+    if runtime_image_type != RuntimeImageType::Jdk {
+        exec_d_programs.push((
+            "heroku_dynamic_jvm_opts",
+            additional_buildpack_binary_path!("heroku_dynamic_jvm_opts"),
+        ));
+    }
+
+    layer_ref.write_exec_d_programs(exec_d_programs)
+}
+
+/// The launch environment of the OpenJDK layer, for when that layer is not available at launch.
+fn launch_env() -> LayerEnv {
+    LayerEnv::new()
+        .chainable_insert(
+            Scope::Launch,
+            ModificationBehavior::Delimiter,
+            JAVA_TOOL_OPTIONS_ENV_VAR_NAME,
+            JAVA_TOOL_OPTIONS_ENV_VAR_DELIMITER,
+        )
+        .chainable_insert(
+            Scope::Launch,
+            ModificationBehavior::Prepend,
+            JAVA_TOOL_OPTIONS_ENV_VAR_NAME,
+            "-Dfile.encoding=UTF-8",
+        )
 }
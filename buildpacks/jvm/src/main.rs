@@ -3,31 +3,46 @@
 // to be able selectively opt out of coverage for functions/lines/modules.
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+mod agents;
 mod constants;
+mod custom_openjdk;
 mod errors;
 mod layers;
+mod mirror;
 mod openjdk_artifact;
 mod openjdk_version;
+mod openjdk_version_range;
 mod salesforce_functions;
+mod sbom;
 mod util;
+mod version_inference;
 mod version_resolver;
 
+use crate::agents::{JavaAgentsConfigError, read_java_agents};
 use crate::constants::OPENJDK_LATEST_LTS_VERSION;
+use crate::custom_openjdk::{CustomOpenJdkTarballError, read_custom_openjdk_tarball};
 use crate::errors::on_error_jvm_buildpack;
-use crate::layers::openjdk::handle_openjdk_layer;
+use crate::layers::agent::handle_agent_layer;
+use crate::layers::openjdk::{OpenJdkTarball, handle_openjdk_layer};
 use crate::layers::runtime::handle_runtime_layer;
+use crate::mirror::FetchError;
 use crate::openjdk_artifact::{
     HerokuOpenJdkVersionRequirement, OpenJdkArtifactMetadata, OpenJdkArtifactRequirement,
 };
 use crate::openjdk_version::OpenJdkVersion;
 use crate::version_resolver::{
-    OpenJdkArtifactRequirementSource, VersionResolveError, resolve_version,
+    OpenJdkArtifactRequirementSource, VersionResolveError, read_configured_version_strings,
+    resolve_version,
 };
 use buildpacks_jvm_shared::output;
 use buildpacks_jvm_shared::output::{
     BuildpackOutputText, BuildpackOutputTextSection, track_buildpack_timing,
 };
-use buildpacks_jvm_shared::system_properties::{ReadSystemPropertiesError, read_system_properties};
+use buildpacks_jvm_shared::runtime_image::{
+    JAVA_RUNTIME_IMAGE_ENV_VAR_NAME, RuntimeImageConfigError, RuntimeImageError, RuntimeImageType,
+    is_runtime_image_type_provided, read_runtime_image_config,
+};
+use buildpacks_jvm_shared::system_properties::read_system_properties;
 #[cfg(test)]
 use buildpacks_jvm_shared_test as _;
 pub(crate) use constants::{
@@ -35,6 +50,7 @@ pub(crate) use constants::{
 };
 use indoc::formatdoc;
 use libcnb::Buildpack;
+use libcnb::Platform;
 use libcnb::build::{BuildContext, BuildResult, BuildResultBuilder};
 use libcnb::buildpack_main;
 use libcnb::data::build_plan::BuildPlanBuilder;
@@ -42,23 +58,29 @@ use libcnb::detect::{DetectContext, DetectResult, DetectResultBuilder};
 use libcnb::generic::{GenericMetadata, GenericPlatform};
 #[cfg(test)]
 use libcnb_test as _;
-use libherokubuildpack::download::DownloadError;
 use libherokubuildpack::inventory::artifact::{Arch, Os};
 use libherokubuildpack::inventory::{Inventory, ParseInventoryError};
+use p12_keystore as _; // Used by exec.d binary
+use pem as _; // Used by exec.d binary
+use pkcs8 as _; // Used by exec.d binary
+use rand as _; // Used by exec.d binary
 use sha2::Sha256;
 use std::env::consts;
-use url as _; // Used by exec.d binary
 
 struct OpenJdkBuildpack;
 
 #[derive(Debug)]
 enum OpenJdkBuildpackError {
-    UnsupportedOpenJdkVersion(OpenJdkArtifactRequirement),
-    OpenJdkDownloadError(DownloadError),
+    UnsupportedOpenJdkVersion(OpenJdkArtifactRequirementSource, OpenJdkArtifactRequirement),
+    OpenJdkDownloadError(FetchError),
+    InvalidOpenJdkMirrorUrl(String, url::ParseError),
+    InvalidOpenJdkDownloadUrl(url::ParseError),
     CannotCreateOpenJdkTempDir(std::io::Error),
     CannotReadOpenJdkTarball(std::io::Error),
-    ReadSystemPropertiesError(ReadSystemPropertiesError),
-    OpenJdkTarballChecksumError { expected: Vec<u8>, actual: Vec<u8> },
+    OpenJdkTarballChecksumError {
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
     CannotDecompressOpenJdkTarball(std::io::Error),
     MissingJdkCertificatesFile,
     CannotSymlinkUbuntuCertificates(std::io::Error),
@@ -66,6 +88,19 @@ enum OpenJdkBuildpackError {
     CannotCopyJdkOverlayContents(fs_extra::error::Error),
     ParseInventoryError(ParseInventoryError),
     ResolveVersionError(VersionResolveError),
+    CustomOpenJdkTarballError(CustomOpenJdkTarballError),
+    ReadRuntimeImageConfigError(RuntimeImageConfigError),
+    CannotCreateRuntimeImage(RuntimeImageError),
+    CannotCreateOpenJdkSbom(std::io::Error),
+    ReadJavaAgentsConfigError(JavaAgentsConfigError),
+    InvalidJavaAgentUrl(String, url::ParseError),
+    JavaAgentDownloadError(String, FetchError),
+    JavaAgentChecksumError {
+        name: String,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+    CannotReadJavaAgent(std::io::Error),
 }
 
 impl Buildpack for OpenJdkBuildpack {
@@ -83,10 +118,18 @@ impl Buildpack for OpenJdkBuildpack {
         // work with this buildpack since "jdk" would not be required in the build plan.
         // To enable this use-case, this buildpack will require "jdk" (itself) if the app contains
         // a system.properties file with a Java version. This is currently the way to define the
-        // OpenJDK version on Heroku.
+        // OpenJDK version on Heroku. Other version files, such as .sdkmanrc, are often committed
+        // for local development only and therefore do not cause "jdk" to be required.
         let app_specifies_jvm_version = read_system_properties(&context.app_dir)
             .map(|properties| properties.contains_key("java.runtime.version"))
-            .map_err(OpenJdkBuildpackError::ReadSystemPropertiesError)?;
+            .map_err(|error| {
+                OpenJdkBuildpackError::ResolveVersionError(
+                    VersionResolveError::ReadSystemPropertiesError(error),
+                )
+            })?
+            || read_custom_openjdk_tarball(&context.app_dir, context.platform.env())
+                .map(|tarball| tarball.is_some())
+                .map_err(OpenJdkBuildpackError::CustomOpenJdkTarballError)?;
 
         let build_plan = if app_specifies_jvm_version {
             BuildPlanBuilder::new().provides("jdk").requires("jdk")
@@ -103,97 +146,92 @@ impl Buildpack for OpenJdkBuildpack {
         track_buildpack_timing(|| {
             output::print_buildpack_name("Heroku OpenJDK Buildpack");
 
-            let resolved_version = resolve_version(&context.app_dir)
-                .map_err(OpenJdkBuildpackError::ResolveVersionError)?;
+            let openjdk_tarball =
+                match read_custom_openjdk_tarball(&context.app_dir, context.platform.env())
+                    .map_err(OpenJdkBuildpackError::CustomOpenJdkTarballError)?
+                {
+                    Some(openjdk_tarball) => {
+                        let ignored_version_sources =
+                            read_configured_version_strings(&context.app_dir)
+                                .map_err(OpenJdkBuildpackError::ResolveVersionError)?;
+
+                        if !ignored_version_sources.is_empty() {
+                            output::print_warning(
+                                "OpenJDK version configuration ignored",
+                                formatdoc! {"
+                            Your application configures a custom OpenJDK tarball. The OpenJDK
+                            version configured in the following files will be ignored: {sources}",
+                                sources = ignored_version_sources
+                                    .iter()
+                                    .map(|(source, _)| source.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")},
+                            );
+                        }
+
+                        output::print_section("OpenJDK version resolution");
+                        output::print_subsection(BuildpackOutputText::new(vec![
+                            BuildpackOutputTextSection::regular(
+                                "Using custom OpenJDK tarball from ",
+                            ),
+                            BuildpackOutputTextSection::value(openjdk_tarball.url.clone()),
+                        ]));
+
+                        openjdk_tarball
+                    }
+                    None => resolve_openjdk_tarball(&context)?,
+                };
+
+            let mut runtime_image_config =
+                read_runtime_image_config(&context.app_dir, context.platform.env())
+                    .map_err(OpenJdkBuildpackError::ReadRuntimeImageConfigError)?;
 
-            if matches!(
-                resolved_version.source,
-                OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+            if !is_runtime_image_type_provided(
+                &context.buildpack_plan.entries,
+                runtime_image_config.image_type,
             ) {
                 output::print_warning(
-                    "No OpenJDK version specified",
+                    "Unsupported Java runtime image type",
                     formatdoc! {"
-                    Your application does not explicitly specify an OpenJDK version. The latest
-                    long-term support (LTS) version will be installed. This currently is OpenJDK {OPENJDK_LATEST_LTS_VERSION}.
+                    Your application is configured to use the '{image_type}' runtime image type. This
+                    type is only supported when the application is built with Maven or Gradle, which
+                    create the runtime after the application has been built.
 
-                    This default version will change when a new LTS version is released. Your
-                    application might fail to build with the new version. We recommend explicitly
-                    setting the required OpenJDK version for your application.
-
-                    To set the OpenJDK version, add or edit the system.properties file in the root
-                    directory of your application to contain:
-
-                    java.runtime.version = {OPENJDK_LATEST_LTS_VERSION}"},
+                    The full OpenJDK will be available at launch instead. To remove this warning,
+                    remove the java.runtime.image property from your system.properties file and
+                    unset the {JAVA_RUNTIME_IMAGE_ENV_VAR_NAME} environment variable.",
+                    image_type = runtime_image_config.image_type.name()},
                 );
+
+                runtime_image_config.image_type = RuntimeImageType::Jdk;
             }
 
-            output::print_section("OpenJDK version resolution");
+            let openjdk_layer_path =
+                handle_openjdk_layer(&context, &openjdk_tarball, runtime_image_config.image_type)?;
 
-            match resolved_version.source {
-                OpenJdkArtifactRequirementSource::SystemProperties => {
-                    output::print_subsection(BuildpackOutputText::new(vec![
-                        BuildpackOutputTextSection::regular("Using version string provided in "),
-                        BuildpackOutputTextSection::value("system.properties"),
-                    ]));
-                }
-                OpenJdkArtifactRequirementSource::DefaultVersionLatestLts => {
-                    output::print_subsection("No explicit configuration found, using latest LTS");
-                }
-                OpenJdkArtifactRequirementSource::DefaultVersionFunctions => {
-                    output::print_subsection(BuildpackOutputText::new(vec![
-                        BuildpackOutputTextSection::regular(
-                            "No explicit configuration found, using ",
-                        ),
-                        BuildpackOutputTextSection::value("8"),
-                    ]));
-                }
-            }
+            handle_runtime_layer(
+                &context,
+                runtime_image_config.image_type,
+                &openjdk_tarball,
+                &openjdk_layer_path,
+            )?;
 
-            let openjdk_inventory = include_str!("../openjdk_inventory.toml")
-                .parse::<Inventory<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>>()
-                .map_err(OpenJdkBuildpackError::ParseInventoryError)?;
-
-            let openjdk_artifact = openjdk_inventory
-                .partial_resolve(
-                    context
-                        .target
-                        .os
-                        .parse::<Os>()
-                        .expect("OS should be always parseable, buildpack will not run on unsupported operating systems."),
-                    // On platform API <= `0.9` together with lifecycle <= `0.17`, the `CNB_TARGET_ARCH` environment variable will not be set.
-                    // This will be the case for the `salesforce-functions` builder. To ensure this buildpack can run there, we will
-                    // fall back to Rust's architecture constant when the architecture cannot be determined. This workaround can be removed when
-                    // the `salesforce-functions` builder is EOL.
-                    Some(context.target.arch.as_str())
-                        .filter(|value| !value.is_empty())
-                        .unwrap_or(consts::ARCH)
-                        .parse::<Arch>()
-                        .expect("arch should be always parseable, buildpack will not run on unsupported architectures."),
-                    &resolved_version.requirement,
-                )
-                .ok_or(OpenJdkBuildpackError::UnsupportedOpenJdkVersion(
-                    resolved_version.requirement.clone(),
-                ))?;
-
-            output::print_subsection(match resolved_version.requirement.version {
-                HerokuOpenJdkVersionRequirement::Major(major_version) => {
-                    BuildpackOutputText::new(vec![
-                        BuildpackOutputTextSection::regular("Selected major version "),
-                        BuildpackOutputTextSection::value(format!("{major_version}")),
-                        BuildpackOutputTextSection::regular(" resolves to "),
-                        BuildpackOutputTextSection::value(format!("{}", openjdk_artifact.version)),
-                    ])
-                }
-                HerokuOpenJdkVersionRequirement::Specific(version) => {
-                    BuildpackOutputText::new(vec![
-                        BuildpackOutputTextSection::regular("Selected version "),
-                        BuildpackOutputTextSection::value(format!("{version}")),
-                    ])
-                }
-            });
+            let java_agents = read_java_agents(&context.app_dir, context.platform.env())
+                .map_err(OpenJdkBuildpackError::ReadJavaAgentsConfigError)?;
+
+            if !java_agents.is_empty() {
+                output::print_section("Installing Java agents");
 
-            handle_openjdk_layer(&context, openjdk_artifact)?;
-            handle_runtime_layer(&context)?;
+                if runtime_image_config.image_type == RuntimeImageType::Native {
+                    output::print_subsection(
+                        "Skipping (Java agents are not supported by native executables)",
+                    );
+                } else {
+                    for java_agent in &java_agents {
+                        handle_agent_layer(&context, java_agent)?;
+                    }
+                }
+            }
 
             BuildResultBuilder::new().build()
         })
@@ -206,6 +244,172 @@ impl Buildpack for OpenJdkBuildpack {
 
 buildpack_main!(OpenJdkBuildpack);
 
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[allow(clippy::too_many_lines)]
+fn resolve_openjdk_tarball(
+    context: &BuildContext<OpenJdkBuildpack>,
+) -> Result<OpenJdkTarball, OpenJdkBuildpackError> {
+    let resolved_version =
+        resolve_version(&context.app_dir).map_err(OpenJdkBuildpackError::ResolveVersionError)?;
+
+    if matches!(
+        resolved_version.source,
+        OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+    ) {
+        output::print_warning(
+            "No OpenJDK version specified",
+            formatdoc! {"
+            Your application does not explicitly specify an OpenJDK version. The latest
+            long-term support (LTS) version will be installed. This currently is OpenJDK {OPENJDK_LATEST_LTS_VERSION}.
+
+            This default version will change when a new LTS version is released. Your
+            application might fail to build with the new version. We recommend explicitly
+            setting the required OpenJDK version for your application.
+
+            To set the OpenJDK version, add or edit the system.properties file in the root
+            directory of your application to contain:
+
+            java.runtime.version = {OPENJDK_LATEST_LTS_VERSION}"},
+        );
+    }
+
+    if let OpenJdkArtifactRequirementSource::BuildToolConfiguration(file_name) =
+        resolved_version.source
+    {
+        output::print_warning(
+            "OpenJDK version inferred from build configuration",
+            formatdoc! {"
+            Your application does not explicitly specify an OpenJDK version. OpenJDK
+            {version} will be installed, as inferred from the Java release version configured
+            in your {file_name} file.
+
+            We recommend explicitly setting the required OpenJDK version for your application.
+            To set the OpenJDK version, add or edit the system.properties file in the root
+            directory of your application to contain:
+
+            java.runtime.version = {version}",
+            version = resolved_version.requirement.version},
+        );
+    }
+
+    if let Some(ignored_distribution) = &resolved_version.ignored_distribution {
+        output::print_warning(
+            "Unsupported OpenJDK distribution",
+            formatdoc! {"
+            Your {source} file requests the OpenJDK distribution '{ignored_distribution}' which
+            is not supported by this buildpack. Only the configured version ({version}) will be
+            used and the default distribution will be installed instead.
+
+            To remove this warning, configure the OpenJDK version in the system.properties file
+            in the root directory of your application:
+
+            java.runtime.version = {version}",
+            source = resolved_version.source,
+            version = resolved_version.requirement.version},
+        );
+    }
+
+    if !resolved_version.conflicting_sources.is_empty() {
+        output::print_warning(
+            "Conflicting OpenJDK version configuration",
+            formatdoc! {"
+            Your application configures different OpenJDK versions in multiple files. The
+            version from {source} will be used, the versions configured in the following
+            files will be ignored: {conflicting_sources}
+
+            Files are considered in this order: system.properties, .java-version, .sdkmanrc
+            and .tool-versions. To avoid confusion, we recommend configuring the same
+            OpenJDK version in all of these files.",
+            source = resolved_version.source,
+            conflicting_sources = resolved_version
+                .conflicting_sources
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")},
+        );
+    }
+
+    output::print_section("OpenJDK version resolution");
+
+    match resolved_version.source {
+        OpenJdkArtifactRequirementSource::SystemProperties
+        | OpenJdkArtifactRequirementSource::JavaVersionFile
+        | OpenJdkArtifactRequirementSource::SdkmanRc
+        | OpenJdkArtifactRequirementSource::ToolVersions => {
+            output::print_subsection(BuildpackOutputText::new(vec![
+                BuildpackOutputTextSection::regular("Using version string provided in "),
+                BuildpackOutputTextSection::value(resolved_version.source.to_string()),
+            ]));
+        }
+        OpenJdkArtifactRequirementSource::BuildToolConfiguration(file_name) => {
+            output::print_subsection(BuildpackOutputText::new(vec![
+                BuildpackOutputTextSection::regular(
+                    "No explicit configuration found, using version inferred from ",
+                ),
+                BuildpackOutputTextSection::value(file_name),
+            ]));
+        }
+        OpenJdkArtifactRequirementSource::DefaultVersionLatestLts => {
+            output::print_subsection("No explicit configuration found, using latest LTS");
+        }
+        OpenJdkArtifactRequirementSource::DefaultVersionFunctions => {
+            output::print_subsection(BuildpackOutputText::new(vec![
+                BuildpackOutputTextSection::regular("No explicit configuration found, using "),
+                BuildpackOutputTextSection::value("8"),
+            ]));
+        }
+    }
+
+    let openjdk_inventory = include_str!("../openjdk_inventory.toml")
+        .parse::<Inventory<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>>()
+        .map_err(OpenJdkBuildpackError::ParseInventoryError)?;
+
+    let openjdk_artifact = openjdk_inventory
+        .partial_resolve(
+            context
+                .target
+                .os
+                .parse::<Os>()
+                .expect("OS should be always parseable, buildpack will not run on unsupported operating systems."),
+            // On platform API <= `0.9` together with lifecycle <= `0.17`, the `CNB_TARGET_ARCH` environment variable will not be set.
+            // This will be the case for the `salesforce-functions` builder. To ensure this buildpack can run there, we will
+            // fall back to Rust's architecture constant when the architecture cannot be determined. This workaround can be removed when
+            // the `salesforce-functions` builder is EOL.
+            Some(context.target.arch.as_str())
+                .filter(|value| !value.is_empty())
+                .unwrap_or(consts::ARCH)
+                .parse::<Arch>()
+                .expect("arch should be always parseable, buildpack will not run on unsupported architectures."),
+            &resolved_version.requirement,
+        )
+        .ok_or(OpenJdkBuildpackError::UnsupportedOpenJdkVersion(
+            resolved_version.source,
+            resolved_version.requirement.clone(),
+        ))?;
+
+    output::print_subsection(match resolved_version.requirement.version {
+        HerokuOpenJdkVersionRequirement::Major(major_version) => BuildpackOutputText::new(vec![
+            BuildpackOutputTextSection::regular("Selected major version "),
+            BuildpackOutputTextSection::value(format!("{major_version}")),
+            BuildpackOutputTextSection::regular(" resolves to "),
+            BuildpackOutputTextSection::value(format!("{}", openjdk_artifact.version)),
+        ]),
+        HerokuOpenJdkVersionRequirement::Specific(version) => BuildpackOutputText::new(vec![
+            BuildpackOutputTextSection::regular("Selected version "),
+            BuildpackOutputTextSection::value(format!("{version}")),
+        ]),
+        HerokuOpenJdkVersionRequirement::Range(range) => BuildpackOutputText::new(vec![
+            BuildpackOutputTextSection::regular("Selected version range "),
+            BuildpackOutputTextSection::value(format!("{range}")),
+            BuildpackOutputTextSection::regular(" resolves to "),
+            BuildpackOutputTextSection::value(format!("{}", openjdk_artifact.version)),
+        ]),
+    });
+
+    Ok(OpenJdkTarball::from(openjdk_artifact))
+}
+
 impl From<OpenJdkBuildpackError> for libcnb::Error<OpenJdkBuildpackError> {
     fn from(error: OpenJdkBuildpackError) -> Self {
         libcnb::Error::BuildpackError(error)
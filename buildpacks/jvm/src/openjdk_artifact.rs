@@ -1,6 +1,8 @@
 use crate::openjdk_version::OpenJdkVersion;
+use crate::openjdk_version_range::OpenJdkVersionRange;
 use libherokubuildpack::inventory::version::{ArtifactRequirement, VersionRequirement};
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -29,15 +31,22 @@ impl FromStr for OpenJdkArtifactRequirement {
                 },
             );
 
-        let version = user_version_string
-            .parse::<u32>()
-            .map(HerokuOpenJdkVersionRequirement::Major)
-            .or_else(|_| {
-                user_version_string
-                    .parse::<OpenJdkVersion>()
-                    .map(HerokuOpenJdkVersionRequirement::Specific)
-                    .map_err(OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError)
-            })?;
+        let version = if OpenJdkVersionRange::is_range_syntax(user_version_string) {
+            user_version_string
+                .parse::<OpenJdkVersionRange>()
+                .map(HerokuOpenJdkVersionRequirement::Range)
+                .map_err(OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError)
+        } else {
+            user_version_string
+                .parse::<u32>()
+                .map(HerokuOpenJdkVersionRequirement::Major)
+                .or_else(|_| {
+                    user_version_string
+                        .parse::<OpenJdkVersion>()
+                        .map(HerokuOpenJdkVersionRequirement::Specific)
+                        .map_err(OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError)
+                })
+        }?;
 
         let distribution = match user_distribution_string {
             None => Ok(OpenJdkDistribution::default()),
@@ -69,6 +78,14 @@ pub(crate) enum OpenJdkDistribution {
     AzulZulu,
 }
 
+impl Display for OpenJdkDistribution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenJdkDistribution::AzulZulu => write!(f, "Azul Zulu"),
+        }
+    }
+}
+
 impl ArtifactRequirement<OpenJdkVersion, OpenJdkArtifactMetadata> for OpenJdkArtifactRequirement {
     fn satisfies_metadata(&self, metadata: &OpenJdkArtifactMetadata) -> bool {
         metadata.distribution == self.distribution
@@ -83,6 +100,17 @@ impl ArtifactRequirement<OpenJdkVersion, OpenJdkArtifactMetadata> for OpenJdkArt
 pub(crate) enum HerokuOpenJdkVersionRequirement {
     Major(u32),
     Specific(OpenJdkVersion),
+    Range(OpenJdkVersionRange),
+}
+
+impl Display for HerokuOpenJdkVersionRequirement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HerokuOpenJdkVersionRequirement::Major(major_version) => write!(f, "{major_version}"),
+            HerokuOpenJdkVersionRequirement::Specific(version) => write!(f, "{version}"),
+            HerokuOpenJdkVersionRequirement::Range(range) => write!(f, "{range}"),
+        }
+    }
 }
 
 impl VersionRequirement<OpenJdkVersion> for HerokuOpenJdkVersionRequirement {
@@ -94,6 +122,7 @@ impl VersionRequirement<OpenJdkVersion> for HerokuOpenJdkVersionRequirement {
             HerokuOpenJdkVersionRequirement::Specific(requested_version) => {
                 version == requested_version
             }
+            HerokuOpenJdkVersionRequirement::Range(range) => range.satisfies(version),
         }
     }
 }
@@ -101,6 +130,10 @@ impl VersionRequirement<OpenJdkVersion> for HerokuOpenJdkVersionRequirement {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indoc::indoc;
+    use libherokubuildpack::inventory::Inventory;
+    use libherokubuildpack::inventory::artifact::{Arch, Os};
+    use sha2::Sha256;
 
     #[test]
     fn test_requirement_from_string() {
@@ -126,6 +159,36 @@ mod tests {
             );
         }
 
+        assert_eq!(
+            "graalvm-21".parse::<OpenJdkArtifactRequirement>(),
+            Err(OpenJdkArtifactRequirementParseError::UnknownDistribution(
+                String::from("graalvm")
+            ))
+        );
+
+        assert_eq!(
+            "zulu->=17.0.9, <18".parse(),
+            Ok(OpenJdkArtifactRequirement {
+                version: HerokuOpenJdkVersionRequirement::Range(">=17.0.9, <18".parse().unwrap()),
+                distribution: zulu,
+            })
+        );
+
+        assert_eq!(
+            "foo-21".parse::<OpenJdkArtifactRequirement>(),
+            Err(OpenJdkArtifactRequirementParseError::UnknownDistribution(
+                String::from("foo")
+            ))
+        );
+
+        assert_eq!(
+            "~21.0.3".parse(),
+            Ok(OpenJdkArtifactRequirement {
+                version: HerokuOpenJdkVersionRequirement::Range("~21.0.3".parse().unwrap()),
+                distribution: zulu,
+            })
+        );
+
         assert_eq!(
             "thx-11.3.8".parse::<OpenJdkArtifactRequirement>(),
             Err(OpenJdkArtifactRequirementParseError::UnknownDistribution(
@@ -168,4 +231,58 @@ mod tests {
         assert!(!requirement.satisfies(&"8u361".parse::<OpenJdkVersion>().unwrap()));
         assert!(!requirement.satisfies(&"7u351".parse::<OpenJdkVersion>().unwrap()));
     }
+
+    #[test]
+    fn test_inventory_resolution() {
+        let inventory = indoc! {r#"
+            [[artifacts]]
+            version = "21.0.5"
+            os = "linux"
+            arch = "amd64"
+            url = "https://example.com/openjdk/zulu/amd64/21.0.5.tar.gz"
+            checksum = "sha256:0000000000000000000000000000000000000000000000000000000000000001"
+
+            [artifacts.metadata]
+            distribution = "zulu"
+
+            [[artifacts]]
+            version = "21.0.2"
+            os = "linux"
+            arch = "amd64"
+            url = "https://example.com/openjdk/zulu/amd64/21.0.2.tar.gz"
+            checksum = "sha256:0000000000000000000000000000000000000000000000000000000000000002"
+
+            [artifacts.metadata]
+            distribution = "zulu"
+        "#}
+        .parse::<Inventory<OpenJdkVersion, Sha256, OpenJdkArtifactMetadata>>()
+        .unwrap();
+
+        for (requirement, expected_version) in [
+            ("21", "21.0.5"),
+            ("zulu-21", "21.0.5"),
+            ("21.0.*, <21.0.5", "21.0.2"),
+        ] {
+            assert_eq!(
+                inventory
+                    .partial_resolve(
+                        Os::Linux,
+                        Arch::Amd64,
+                        &requirement.parse::<OpenJdkArtifactRequirement>().unwrap(),
+                    )
+                    .map(|artifact| artifact.version.to_string()),
+                Some(String::from(expected_version))
+            );
+        }
+
+        assert!(
+            inventory
+                .partial_resolve(
+                    Os::Linux,
+                    Arch::Amd64,
+                    &"zulu-21.0.3".parse::<OpenJdkArtifactRequirement>().unwrap(),
+                )
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,275 @@
+use crate::openjdk_version::OpenJdkVersion;
+use libherokubuildpack::inventory::version::VersionRequirement;
+use nom::Finish;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A set of OpenJDK version constraints that all need to be satisfied by a version.
+///
+/// Supported constraint syntax, separated by commas:
+/// - Comparisons: `>=17.0.9`, `>17`, `<18`, `<=21.0.3`, `=21.0.3`
+/// - Wildcards: `21.*`, `21.0.*`
+/// - Tilde ranges: `~21` (any 21 version), `~21.0.3` (at least 21.0.3, but below 21.1)
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct OpenJdkVersionRange {
+    source: String,
+    constraints: Vec<OpenJdkVersionConstraint>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum OpenJdkVersionConstraint {
+    Exact(OpenJdkVersion),
+    Greater(OpenJdkVersion),
+    GreaterOrEqual(OpenJdkVersion),
+    Less(OpenJdkVersion),
+    LessOrEqual(OpenJdkVersion),
+    Tilde(Vec<u32>),
+    Wildcard(Vec<u32>),
+}
+
+impl OpenJdkVersionRange {
+    /// Checks if the given string uses range syntax rather than being a plain version string.
+    pub(crate) fn is_range_syntax(s: &str) -> bool {
+        s.contains(['<', '>', '=', '~', '*', ','])
+    }
+}
+
+impl VersionRequirement<OpenJdkVersion> for OpenJdkVersionRange {
+    fn satisfies(&self, version: &OpenJdkVersion) -> bool {
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.satisfies(version))
+    }
+}
+
+impl OpenJdkVersionConstraint {
+    fn satisfies(&self, version: &OpenJdkVersion) -> bool {
+        // Legacy and JEP 322 versions of the same major version are not comparable. Such versions
+        // never satisfy a comparison constraint.
+        let ordering = |bound: &OpenJdkVersion| version.partial_cmp(bound);
+
+        match self {
+            OpenJdkVersionConstraint::Exact(bound) => ordering(bound) == Some(Ordering::Equal),
+            OpenJdkVersionConstraint::Greater(bound) => ordering(bound) == Some(Ordering::Greater),
+            OpenJdkVersionConstraint::GreaterOrEqual(bound) => {
+                matches!(ordering(bound), Some(Ordering::Greater | Ordering::Equal))
+            }
+            OpenJdkVersionConstraint::Less(bound) => ordering(bound) == Some(Ordering::Less),
+            OpenJdkVersionConstraint::LessOrEqual(bound) => {
+                matches!(ordering(bound), Some(Ordering::Less | Ordering::Equal))
+            }
+            OpenJdkVersionConstraint::Tilde(elements) => {
+                // Like in semver, a tilde allows changes to the last element if only the major
+                // version is given and only changes after the second element otherwise.
+                let prefix_length = elements.len().min(2);
+
+                matches_prefix(version, &elements[..prefix_length])
+                    && compare_elements(version, elements)
+                        .is_some_and(|ordering| ordering != Ordering::Less)
+            }
+            OpenJdkVersionConstraint::Wildcard(prefix) => matches_prefix(version, prefix),
+        }
+    }
+}
+
+/// Returns the version element at the given index, filling in omitted trailing zeroes.
+///
+/// Legacy versions only have a comparable major element.
+fn version_element(version: &OpenJdkVersion, index: usize) -> Option<u32> {
+    match (version, index) {
+        (_, 0) => Some(version.major()),
+        (
+            OpenJdkVersion::Jep322 {
+                remaining_elements, ..
+            },
+            index,
+        ) => Some(remaining_elements.get(index - 1).copied().unwrap_or(0)),
+        (OpenJdkVersion::Legacy { .. }, _) => None,
+    }
+}
+
+fn matches_prefix(version: &OpenJdkVersion, prefix: &[u32]) -> bool {
+    prefix
+        .iter()
+        .enumerate()
+        .all(|(index, element)| version_element(version, index) == Some(*element))
+}
+
+fn compare_elements(version: &OpenJdkVersion, elements: &[u32]) -> Option<Ordering> {
+    elements
+        .iter()
+        .enumerate()
+        .map(|(index, element)| version_element(version, index).map(|value| value.cmp(element)))
+        .find(|ordering| *ordering != Some(Ordering::Equal))
+        .unwrap_or(Some(Ordering::Equal))
+}
+
+impl Display for OpenJdkVersionRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for OpenJdkVersionRange {
+    type Err = nom::error::Error<String>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse::range(s.trim()).finish() {
+            Ok((_, constraints)) => Ok(OpenJdkVersionRange {
+                source: String::from(s.trim()),
+                constraints,
+            }),
+            Err(nom::error::Error { input, code }) => Err(nom::error::Error {
+                input: String::from(input),
+                code,
+            }),
+        }
+    }
+}
+
+mod parse {
+    use super::OpenJdkVersionConstraint;
+    use crate::openjdk_version::OpenJdkVersion;
+    use nom::IResult;
+    use nom::branch::alt;
+    use nom::bytes::complete::{tag, take_while1};
+    use nom::character::complete::{char, digit1, space0};
+    use nom::combinator::{eof, map, map_res};
+    use nom::multi::separated_list1;
+    use nom::sequence::{delimited, preceded, terminated, tuple};
+
+    pub(super) fn range(input: &str) -> IResult<&str, Vec<OpenJdkVersionConstraint>> {
+        terminated(
+            separated_list1(char(','), delimited(space0, constraint, space0)),
+            eof,
+        )(input)
+    }
+
+    fn constraint(input: &str) -> IResult<&str, OpenJdkVersionConstraint> {
+        alt((
+            map(
+                preceded(char('~'), elements),
+                OpenJdkVersionConstraint::Tilde,
+            ),
+            map(
+                terminated(elements, tag(".*")),
+                OpenJdkVersionConstraint::Wildcard,
+            ),
+            map(
+                tuple((
+                    alt((tag(">="), tag("<="), tag(">"), tag("<"), tag("="))),
+                    preceded(space0, version),
+                )),
+                |(operator, version)| match operator {
+                    ">=" => OpenJdkVersionConstraint::GreaterOrEqual(version),
+                    "<=" => OpenJdkVersionConstraint::LessOrEqual(version),
+                    ">" => OpenJdkVersionConstraint::Greater(version),
+                    "<" => OpenJdkVersionConstraint::Less(version),
+                    _ => OpenJdkVersionConstraint::Exact(version),
+                },
+            ),
+            map(version, OpenJdkVersionConstraint::Exact),
+        ))(input)
+    }
+
+    fn version(input: &str) -> IResult<&str, OpenJdkVersion> {
+        map_res(
+            take_while1(|c: char| c != ',' && !c.is_whitespace()),
+            str::parse::<OpenJdkVersion>,
+        )(input)
+    }
+
+    // Unlike the version parser, trailing zeroes are kept since they are significant for
+    // wildcard and tilde constraints.
+    fn elements(input: &str) -> IResult<&str, Vec<u32>> {
+        separated_list1(char('.'), map_res(digit1, str::parse::<u32>))(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(range: &str, version: &str) -> bool {
+        range
+            .parse::<OpenJdkVersionRange>()
+            .unwrap()
+            .satisfies(&version.parse::<OpenJdkVersion>().unwrap())
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert!(satisfies(">=17.0.9, <18", "17.0.9"));
+        assert!(satisfies(">=17.0.9, <18", "17.0.12"));
+        assert!(!satisfies(">=17.0.9, <18", "17.0.8"));
+        assert!(!satisfies(">=17.0.9, <18", "18.0.1"));
+
+        assert!(satisfies(">17", "17.0.1"));
+        assert!(!satisfies(">17", "17"));
+        assert!(satisfies("<=21.0.3", "21.0.3"));
+        assert!(!satisfies("<=21.0.3", "21.0.4"));
+        assert!(satisfies("=21.0.3", "21.0.3.0"));
+        assert!(satisfies(">= 11,<= 11.0.5", "11.0.5"));
+
+        assert!(satisfies(">=1.8.0_400", "8u412"));
+        assert!(!satisfies(">=1.8.0_400", "1.8.0_392"));
+        // Legacy and JEP 322 versions of the same major version cannot be compared
+        assert!(!satisfies(">=8.0.1", "8u412"));
+    }
+
+    #[test]
+    fn test_wildcard() {
+        assert!(satisfies("21.0.*", "21.0.5"));
+        assert!(satisfies("21.0.*", "21"));
+        assert!(!satisfies("21.0.*", "21.1.0"));
+        assert!(!satisfies("21.0.*", "22.0.1"));
+
+        assert!(satisfies("8.*", "1.8.0_412"));
+        assert!(!satisfies("8.0.*", "1.8.0_412"));
+    }
+
+    #[test]
+    fn test_tilde() {
+        assert!(satisfies("~21.0.3", "21.0.3"));
+        assert!(satisfies("~21.0.3", "21.0.5.1"));
+        assert!(!satisfies("~21.0.3", "21.0.2"));
+        assert!(!satisfies("~21.0.3", "21.1.0"));
+
+        assert!(satisfies("~21", "21.3"));
+        assert!(!satisfies("~21", "22"));
+
+        assert!(satisfies("~21.1", "21.1.4"));
+        assert!(!satisfies("~21.1", "21.2"));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            " >=17.0.9, <18 "
+                .parse::<OpenJdkVersionRange>()
+                .unwrap()
+                .to_string(),
+            ">=17.0.9, <18"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [">=", "~", "21.0.*, ", ">=17 <18", "~21.x", "*"] {
+            assert!(
+                input.parse::<OpenJdkVersionRange>().is_err(),
+                "{input} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_range_syntax() {
+        assert!(OpenJdkVersionRange::is_range_syntax(">=17"));
+        assert!(OpenJdkVersionRange::is_range_syntax("21.0.*"));
+        assert!(OpenJdkVersionRange::is_range_syntax("~21"));
+        assert!(!OpenJdkVersionRange::is_range_syntax("21.0.3"));
+        assert!(!OpenJdkVersionRange::is_range_syntax("1.8.0_412"));
+    }
+}
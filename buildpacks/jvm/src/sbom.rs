@@ -0,0 +1,287 @@
+use crate::layers::openjdk::OpenJdkTarball;
+use crate::openjdk_artifact::OpenJdkDistribution;
+use buildpacks_jvm_shared::result::default_on_not_found;
+use libcnb::data::sbom::SbomFormat;
+use libcnb::sbom::Sbom;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Creates `CycloneDX` and SPDX SBOMs that describe the given OpenJDK tarball, installed at
+/// `java_home`.
+///
+/// The exact version and vendor are read from the `release` file of the installation. For
+/// inventory tarballs, the inventory version is used as a fallback.
+pub(crate) fn openjdk_sboms(
+    tarball: &OpenJdkTarball,
+    java_home: &Path,
+) -> std::io::Result<Vec<Sbom>> {
+    let release = default_on_not_found(std::fs::read_to_string(java_home.join("release")))
+        .map(|contents| parse_release_file(&contents))?;
+
+    let component = OpenJdkComponent::new(tarball, &release);
+
+    Ok(vec![
+        Sbom::from_bytes(
+            SbomFormat::CycloneDxJson,
+            component.cyclonedx_json().to_string(),
+        ),
+        Sbom::from_bytes(
+            SbomFormat::SpdxJson,
+            component
+                .spdx_json(&rfc3339_timestamp(SystemTime::now()))
+                .to_string(),
+        ),
+    ])
+}
+
+struct OpenJdkComponent {
+    name: String,
+    version: String,
+    vendor: Option<String>,
+    distribution: Option<OpenJdkDistribution>,
+    url: String,
+    sha256: String,
+}
+
+impl OpenJdkComponent {
+    fn new(tarball: &OpenJdkTarball, release: &HashMap<String, String>) -> Self {
+        let name = match tarball.distribution {
+            Some(OpenJdkDistribution::AzulZulu) => "zulu-openjdk",
+            None => "openjdk",
+        };
+
+        OpenJdkComponent {
+            name: String::from(name),
+            version: release
+                .get("JAVA_RUNTIME_VERSION")
+                .or_else(|| release.get("JAVA_VERSION"))
+                .cloned()
+                .or_else(|| tarball.version.as_ref().map(ToString::to_string))
+                .unwrap_or_else(|| String::from("unknown")),
+            vendor: release.get("IMPLEMENTOR").cloned(),
+            distribution: tarball.distribution,
+            url: tarball.url.clone(),
+            sha256: hex::encode(&tarball.checksum.value),
+        }
+    }
+
+    fn purl(&self) -> String {
+        format!(
+            "pkg:generic/{}@{}?{}",
+            self.name,
+            url::form_urlencoded::byte_serialize(self.version.as_bytes()).collect::<String>(),
+            url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("download_url", &self.url)
+                .append_pair("checksum", &format!("sha256:{}", self.sha256))
+                .finish()
+        )
+    }
+
+    fn cyclonedx_json(&self) -> serde_json::Value {
+        let mut properties = Vec::new();
+        if let Some(distribution) = self.distribution {
+            properties.push(json!({
+                "name": "heroku:openjdk:distribution",
+                "value": distribution.to_string(),
+            }));
+        }
+
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.4",
+            "version": 1,
+            "components": [{
+                "type": "application",
+                "bom-ref": self.purl(),
+                "name": self.name,
+                "version": self.version,
+                "publisher": self.vendor,
+                "purl": self.purl(),
+                "hashes": [{ "alg": "SHA-256", "content": self.sha256 }],
+                "licenses": [{ "expression": OPENJDK_LICENSE }],
+                "externalReferences": [{ "type": "distribution", "url": self.url }],
+                "properties": properties,
+            }],
+        })
+    }
+
+    fn spdx_json(&self, created: &str) -> serde_json::Value {
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": format!("{}-{}", self.name, self.version),
+            "documentNamespace": format!(
+                "https://github.com/heroku/buildpacks-jvm/spdx/{}-{}",
+                self.name, self.sha256
+            ),
+            "creationInfo": {
+                "created": created,
+                "creators": ["Tool: heroku/jvm"],
+            },
+            "packages": [{
+                "name": self.name,
+                "SPDXID": "SPDXRef-Package-OpenJDK",
+                "versionInfo": self.version,
+                "supplier": self.vendor.as_ref().map_or_else(
+                    || String::from("NOASSERTION"),
+                    |vendor| format!("Organization: {vendor}"),
+                ),
+                "downloadLocation": self.url,
+                "filesAnalyzed": false,
+                "checksums": [{ "algorithm": "SHA256", "checksumValue": self.sha256 }],
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": OPENJDK_LICENSE,
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": self.purl(),
+                }],
+            }],
+            "relationships": [{
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": "SPDXRef-Package-OpenJDK",
+            }],
+        })
+    }
+}
+
+/// Parses the `release` file of an OpenJDK installation, i.e. `JAVA_VERSION="21.0.5"`.
+fn parse_release_file(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            (
+                String::from(key.trim()),
+                String::from(value.trim().trim_matches('"')),
+            )
+        })
+        .collect()
+}
+
+fn rfc3339_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    // Converts days since the epoch to a civil date, see:
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = seconds / 86_400;
+    let era = (days + 719_468) / 146_097;
+    let day_of_era = (days + 719_468) % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+const OPENJDK_LICENSE: &str = "GPL-2.0-only WITH Classpath-exception-2.0";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libherokubuildpack::inventory::checksum::Checksum;
+    use std::time::Duration;
+
+    const SHA256: &str = "7cafe7cc263ba63115fb801049efb9bfae762c46f252a451ba05734224cb42da";
+
+    fn inventory_tarball() -> OpenJdkTarball {
+        OpenJdkTarball {
+            url: String::from(
+                "https://heroku-buildpacks-jvm.s3.dualstack.us-east-1.amazonaws.com/openjdk/zulu/amd64/21.0.5.tar.gz",
+            ),
+            checksum: format!("sha256:{SHA256}").parse::<Checksum<_>>().unwrap(),
+            from_inventory: true,
+            version: Some("21.0.5".parse().unwrap()),
+            distribution: Some(OpenJdkDistribution::AzulZulu),
+        }
+    }
+
+    #[test]
+    fn component_from_release_file() {
+        let release = parse_release_file(
+            "IMPLEMENTOR=\"Azul Systems, Inc.\"\nJAVA_RUNTIME_VERSION=\"21.0.5+11-LTS\"\nJAVA_VERSION=\"21.0.5\"\n",
+        );
+
+        let component = OpenJdkComponent::new(&inventory_tarball(), &release);
+
+        assert_eq!(component.name, "zulu-openjdk");
+        assert_eq!(component.version, "21.0.5+11-LTS");
+        assert_eq!(component.vendor.as_deref(), Some("Azul Systems, Inc."));
+        assert_eq!(
+            component.purl(),
+            format!(
+                "pkg:generic/zulu-openjdk@21.0.5%2B11-LTS?download_url=https%3A%2F%2Fheroku-buildpacks-jvm.s3.dualstack.us-east-1.amazonaws.com%2Fopenjdk%2Fzulu%2Famd64%2F21.0.5.tar.gz&checksum=sha256%3A{SHA256}"
+            )
+        );
+    }
+
+    #[test]
+    fn component_without_release_file() {
+        let component = OpenJdkComponent::new(&inventory_tarball(), &HashMap::new());
+        assert_eq!(component.version, "21.0.5");
+        assert_eq!(component.vendor, None);
+
+        let custom_tarball = OpenJdkTarball {
+            from_inventory: false,
+            version: None,
+            distribution: None,
+            ..inventory_tarball()
+        };
+
+        let component = OpenJdkComponent::new(&custom_tarball, &HashMap::new());
+        assert_eq!(component.name, "openjdk");
+        assert_eq!(component.version, "unknown");
+    }
+
+    #[test]
+    fn cyclonedx_and_spdx_documents() {
+        let component = OpenJdkComponent::new(&inventory_tarball(), &HashMap::new());
+
+        let cyclonedx = component.cyclonedx_json();
+        assert_eq!(cyclonedx["components"][0]["version"], "21.0.5");
+        assert_eq!(cyclonedx["components"][0]["hashes"][0]["content"], SHA256);
+        assert_eq!(
+            cyclonedx["components"][0]["properties"][0]["value"],
+            "Azul Zulu"
+        );
+
+        let spdx = component.spdx_json("2026-10-18T12:00:00Z");
+        assert_eq!(spdx["packages"][0]["versionInfo"], "21.0.5");
+        assert_eq!(spdx["packages"][0]["supplier"], "NOASSERTION");
+        assert_eq!(spdx["packages"][0]["checksums"][0]["checksumValue"], SHA256);
+    }
+
+    #[test]
+    fn timestamp_formatting() {
+        assert_eq!(rfc3339_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            rfc3339_timestamp(UNIX_EPOCH + Duration::from_hours(497_868)),
+            "2026-10-18T12:00:00Z"
+        );
+        assert_eq!(
+            rfc3339_timestamp(UNIX_EPOCH + Duration::from_hours(264_384)),
+            "2000-02-29T00:00:00Z"
+        );
+    }
+}
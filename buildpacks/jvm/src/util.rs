@@ -60,3 +60,61 @@ where
         }
     }
 }
+
+/// Moves the contents of a single top-level directory up into the given directory.
+///
+/// Does nothing if the directory contains anything other than exactly one directory.
+pub(crate) fn flatten_single_directory<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
+    let contents = list_directory_contents(path.as_ref())?;
+
+    if let [single_directory] = contents.as_slice()
+        && single_directory.is_dir()
+    {
+        // Renaming to a temporary name first allows the nested directory to contain an entry
+        // with the same name as itself.
+        let temporary_path = path.as_ref().join(".flatten-single-directory");
+        std::fs::rename(single_directory, &temporary_path)?;
+
+        for nested_path in list_directory_contents(&temporary_path)? {
+            if let Some(file_name) = nested_path.file_name() {
+                std::fs::rename(&nested_path, path.as_ref().join(file_name))?;
+            }
+        }
+
+        std::fs::remove_dir(temporary_path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn flatten_single_directory_moves_nested_contents() {
+        let temp_dir = tempdir().unwrap();
+        let nested_dir = temp_dir.path().join("jdk-21.0.5+11");
+        std::fs::create_dir_all(nested_dir.join("bin")).unwrap();
+        std::fs::write(nested_dir.join("release"), "JAVA_VERSION=\"21.0.5\"").unwrap();
+
+        flatten_single_directory(temp_dir.path()).unwrap();
+
+        assert!(temp_dir.path().join("bin").is_dir());
+        assert!(temp_dir.path().join("release").is_file());
+        assert!(!nested_dir.exists());
+    }
+
+    #[test]
+    fn flatten_single_directory_keeps_flat_contents() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("bin")).unwrap();
+        std::fs::write(temp_dir.path().join("release"), "").unwrap();
+
+        flatten_single_directory(temp_dir.path()).unwrap();
+
+        assert!(temp_dir.path().join("bin").is_dir());
+        assert!(temp_dir.path().join("release").is_file());
+    }
+}
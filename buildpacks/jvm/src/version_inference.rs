@@ -0,0 +1,307 @@
+use buildpacks_jvm_shared::result::none_on_not_found;
+use std::path::Path;
+
+/// Statically infers the OpenJDK major version from the build tool configuration of the app.
+///
+/// This is a best-effort heuristic that only looks at the build files in the root directory of
+/// the app. Property references or versions computed by the build cannot be inferred.
+pub(crate) fn infer_major_version(
+    app_dir: &Path,
+) -> Result<Option<(&'static str, u32)>, (&'static str, std::io::Error)> {
+    for (file_name, infer) in [
+        ("pom.xml", infer_from_pom as fn(&str) -> Option<u32>),
+        ("build.gradle.kts", infer_from_gradle_build_script),
+        ("build.gradle", infer_from_gradle_build_script),
+        ("build.sbt", infer_from_sbt_build_definition),
+    ] {
+        let inferred_version = none_on_not_found(std::fs::read_to_string(app_dir.join(file_name)))
+            .map_err(|error| (file_name, error))?
+            .and_then(|contents| infer(&contents))
+            // OpenJDK versions before 8 are not supported, such values are most likely not
+            // meant as the runtime version.
+            .filter(|major_version| *major_version >= 8);
+
+        if let Some(major_version) = inferred_version {
+            return Ok(Some((file_name, major_version)));
+        }
+    }
+
+    Ok(None)
+}
+
+fn infer_from_pom(contents: &str) -> Option<u32> {
+    let contents = strip_xml_comments(contents);
+
+    // Only the release settings are considered, as they require an OpenJDK of at least that
+    // version. Settings such as `maven.compiler.target` are also used to build for older versions
+    // with a more recent OpenJDK.
+    ["maven.compiler.release", "release"]
+        .into_iter()
+        .find_map(|element_name| {
+            xml_element_values(&contents, element_name)
+                .into_iter()
+                .find_map(|value| {
+                    // The compiler plugin configuration commonly refers to a property, i.e.
+                    // `<release>${java.version}</release>`.
+                    match value
+                        .trim()
+                        .strip_prefix("${")
+                        .and_then(|value| value.strip_suffix('}'))
+                    {
+                        Some(property_name) => xml_element_values(&contents, property_name)
+                            .into_iter()
+                            .find_map(parse_java_version),
+                        None => parse_java_version(value),
+                    }
+                })
+        })
+}
+
+fn xml_element_values<'a>(contents: &'a str, element_name: &str) -> Vec<&'a str> {
+    let start_tag = format!("<{element_name}>");
+    let end_tag = format!("</{element_name}>");
+
+    contents
+        .match_indices(&start_tag)
+        .filter_map(|(index, _)| {
+            contents[index + start_tag.len()..]
+                .split_once(&end_tag)
+                .map(|(value, _)| value)
+        })
+        .collect()
+}
+
+fn infer_from_gradle_build_script(contents: &str) -> Option<u32> {
+    [
+        // java { toolchain { languageVersion = JavaLanguageVersion.of(21) } }
+        "JavaLanguageVersion.of(",
+        // kotlin { jvmToolchain(21) }
+        "jvmToolchain(",
+    ]
+    .into_iter()
+    .find_map(|prefix| {
+        contents.match_indices(prefix).find_map(|(index, _)| {
+            let value = &contents[index + prefix.len()..];
+            let value_end = value
+                .find(|c: char| !(c.is_ascii_digit() || c == '"' || c == '\''))
+                .unwrap_or(value.len());
+
+            parse_java_version(&value[..value_end].replace(['"', '\''], ""))
+        })
+    })
+}
+
+fn infer_from_sbt_build_definition(contents: &str) -> Option<u32> {
+    // javacOptions ++= Seq("--release", "17") or javacOptions ++= Seq("--release=17")
+    contents
+        .match_indices("\"--release")
+        .find_map(|(index, _)| {
+            contents[index + "\"--release".len()..]
+                .trim_start_matches(['=', '"', ',', ' '])
+                .split('"')
+                .next()
+                .and_then(parse_java_version)
+        })
+}
+
+/// Parses Java version strings as used in build configuration, i.e. `21` or `1.8`.
+fn parse_java_version(value: &str) -> Option<u32> {
+    let value = value.trim();
+
+    value
+        .strip_prefix("1.")
+        .unwrap_or(value)
+        .parse::<u32>()
+        .ok()
+}
+
+fn strip_xml_comments(contents: &str) -> String {
+    let mut result = String::new();
+    let mut remaining = contents;
+
+    while let Some((before_comment, after_comment_start)) = remaining.split_once("<!--") {
+        result.push_str(before_comment);
+        remaining = after_comment_start
+            .split_once("-->")
+            .map_or("", |(_, after_comment)| after_comment);
+    }
+
+    result.push_str(remaining);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn infer_from_pom_properties() {
+        assert_eq!(
+            infer_from_pom(indoc! {"
+                <project>
+                    <properties>
+                        <!-- <maven.compiler.release>11</maven.compiler.release> -->
+                        <maven.compiler.release>21</maven.compiler.release>
+                    </properties>
+                </project>
+            "}),
+            Some(21)
+        );
+
+        assert_eq!(
+            infer_from_pom(indoc! {"
+                <project>
+                    <properties>
+                        <java.version>17</java.version>
+                    </properties>
+                </project>
+            "}),
+            None
+        );
+
+        assert_eq!(
+            infer_from_pom(indoc! {"
+                <project>
+                    <properties>
+                        <maven.compiler.target>1.8</maven.compiler.target>
+                    </properties>
+                </project>
+            "}),
+            None
+        );
+    }
+
+    #[test]
+    fn infer_from_pom_compiler_plugin() {
+        assert_eq!(
+            infer_from_pom(indoc! {"
+                <project>
+                    <properties>
+                        <java.version>17</java.version>
+                    </properties>
+                    <build>
+                        <plugins>
+                            <plugin>
+                                <artifactId>maven-compiler-plugin</artifactId>
+                                <configuration>
+                                    <release>${java.version}</release>
+                                </configuration>
+                            </plugin>
+                        </plugins>
+                    </build>
+                </project>
+            "}),
+            Some(17)
+        );
+
+        assert_eq!(
+            infer_from_pom(indoc! {"
+                <project>
+                    <build>
+                        <plugins>
+                            <plugin>
+                                <artifactId>maven-compiler-plugin</artifactId>
+                                <configuration>
+                                    <release>11</release>
+                                </configuration>
+                            </plugin>
+                        </plugins>
+                    </build>
+                </project>
+            "}),
+            Some(11)
+        );
+
+        assert_eq!(infer_from_pom("<project></project>"), None);
+    }
+
+    #[test]
+    fn infer_from_gradle() {
+        assert_eq!(
+            infer_from_gradle_build_script(indoc! {"
+                java {
+                    toolchain {
+                        languageVersion = JavaLanguageVersion.of(21)
+                    }
+                }
+            "}),
+            Some(21)
+        );
+
+        assert_eq!(
+            infer_from_gradle_build_script(indoc! {"
+                java {
+                    toolchain {
+                        languageVersion.set(JavaLanguageVersion.of(\"17\"))
+                    }
+                }
+            "}),
+            Some(17)
+        );
+
+        assert_eq!(
+            infer_from_gradle_build_script("kotlin { jvmToolchain(17) }"),
+            Some(17)
+        );
+
+        assert_eq!(
+            infer_from_gradle_build_script("sourceCompatibility = JavaVersion.VERSION_1_8"),
+            None
+        );
+
+        assert_eq!(
+            infer_from_gradle_build_script(
+                "languageVersion = JavaLanguageVersion.of(libs.versions.java.get())"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn infer_from_sbt() {
+        assert_eq!(
+            infer_from_sbt_build_definition(r#"javacOptions ++= Seq("--release", "17")"#),
+            Some(17)
+        );
+
+        assert_eq!(
+            infer_from_sbt_build_definition(r#"javacOptions ++= Seq("--release=21")"#),
+            Some(21)
+        );
+
+        assert_eq!(
+            infer_from_sbt_build_definition(r#"scalacOptions ++= Seq("-release", "17")"#),
+            None
+        );
+    }
+
+    #[test]
+    fn infer_major_version_from_app_dir() {
+        let app_dir = tempdir().unwrap();
+        assert_eq!(infer_major_version(app_dir.path()).unwrap(), None);
+
+        std::fs::write(
+            app_dir.path().join("build.gradle"),
+            "java { toolchain { languageVersion = JavaLanguageVersion.of(11) } }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            infer_major_version(app_dir.path()).unwrap(),
+            Some(("build.gradle", 11))
+        );
+
+        std::fs::write(
+            app_dir.path().join("pom.xml"),
+            "<project><properties><maven.compiler.release>7</maven.compiler.release></properties></project>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            infer_major_version(app_dir.path()).unwrap(),
+            Some(("build.gradle", 11))
+        );
+    }
+}
@@ -4,24 +4,41 @@ use crate::openjdk_artifact::{
     OpenJdkArtifactRequirementParseError, OpenJdkDistribution,
 };
 use crate::salesforce_functions::is_salesforce_function_app;
+use crate::version_inference::infer_major_version;
+use buildpacks_jvm_shared::result::none_on_not_found;
 use buildpacks_jvm_shared::system_properties::{ReadSystemPropertiesError, read_system_properties};
+use std::fmt::{Display, Formatter};
 use std::path::Path;
 
 pub(crate) fn resolve_version(app_dir: &Path) -> Result<ResolveResult, VersionResolveError> {
-    let openjdk_artifact_requirement = read_system_properties(app_dir)
-        .map_err(VersionResolveError::ReadSystemPropertiesError)
-        .map(|properties| properties.get("java.runtime.version").cloned())
-        .and_then(|string| {
-            string
-                .map(|string| {
-                    string
-                        .parse::<OpenJdkArtifactRequirement>()
-                        .map_err(VersionResolveError::OpenJdkArtifactRequirementParseError)
-                })
-                .transpose()
-        })?;
+    let mut configured_requirements =
+        read_configured_version_strings(app_dir)?
+            .into_iter()
+            .map(|(source, version_string)| {
+                let (version_string, ignored_distribution) =
+                    normalize_version_string(source, &version_string);
+
+                version_string
+                    .parse::<OpenJdkArtifactRequirement>()
+                    .map(|requirement| (source, requirement, ignored_distribution))
+                    .map_err(|error| {
+                        VersionResolveError::OpenJdkArtifactRequirementParseError(source, error)
+                    })
+            });
 
-    let result = match openjdk_artifact_requirement {
+    let result = match configured_requirements.next().transpose()? {
+        Some((source, requirement, ignored_distribution)) => ResolveResult {
+            // Only the source with the highest precedence has to be valid. Other sources that
+            // cannot be parsed are ignored as they have no effect on the installed version.
+            conflicting_sources: configured_requirements
+                .filter_map(Result::ok)
+                .filter(|(_, other_requirement, _)| *other_requirement != requirement)
+                .map(|(other_source, _, _)| other_source)
+                .collect(),
+            source,
+            requirement,
+            ignored_distribution,
+        },
         // The default version for Salesforce functions is always OpenJDK 8. Keep this conditional
         // around until Salesforce functions is EOL and then remove it.
         None if is_salesforce_function_app(app_dir) => ResolveResult {
@@ -30,39 +47,508 @@ pub(crate) fn resolve_version(app_dir: &Path) -> Result<ResolveResult, VersionRe
                 version: HerokuOpenJdkVersionRequirement::Major(8),
                 distribution: OpenJdkDistribution::default(),
             },
+            conflicting_sources: vec![],
+            ignored_distribution: None,
         },
-        None => ResolveResult {
-            source: OpenJdkArtifactRequirementSource::DefaultVersionLatestLts,
-            requirement: OpenJdkArtifactRequirement {
-                version: HerokuOpenJdkVersionRequirement::Major(OPENJDK_LATEST_LTS_VERSION),
-                distribution: OpenJdkDistribution::default(),
+        None => match infer_major_version(app_dir).map_err(|(file_name, error)| {
+            VersionResolveError::ReadVersionFileError(
+                OpenJdkArtifactRequirementSource::BuildToolConfiguration(file_name),
+                error,
+            )
+        })? {
+            Some((file_name, major_version)) => ResolveResult {
+                source: OpenJdkArtifactRequirementSource::BuildToolConfiguration(file_name),
+                requirement: OpenJdkArtifactRequirement {
+                    version: HerokuOpenJdkVersionRequirement::Major(major_version),
+                    distribution: OpenJdkDistribution::default(),
+                },
+                conflicting_sources: vec![],
+                ignored_distribution: None,
+            },
+            None => ResolveResult {
+                source: OpenJdkArtifactRequirementSource::DefaultVersionLatestLts,
+                requirement: OpenJdkArtifactRequirement {
+                    version: HerokuOpenJdkVersionRequirement::Major(OPENJDK_LATEST_LTS_VERSION),
+                    distribution: OpenJdkDistribution::default(),
+                },
+                conflicting_sources: vec![],
+                ignored_distribution: None,
             },
-        },
-        Some(requirement) => ResolveResult {
-            source: OpenJdkArtifactRequirementSource::SystemProperties,
-            requirement,
         },
     };
 
     Ok(result)
 }
 
+/// Reads the raw OpenJDK version strings from all supported configuration files, ordered by
+/// precedence (highest first). Files that do not exist or do not configure a version are skipped.
+pub(crate) fn read_configured_version_strings(
+    app_dir: &Path,
+) -> Result<Vec<(OpenJdkArtifactRequirementSource, String)>, VersionResolveError> {
+    let mut result = Vec::new();
+
+    if let Some(version_string) = read_system_properties(app_dir)
+        .map_err(VersionResolveError::ReadSystemPropertiesError)?
+        .get("java.runtime.version")
+    {
+        result.push((
+            OpenJdkArtifactRequirementSource::SystemProperties,
+            version_string.clone(),
+        ));
+    }
+
+    for (source, extract_version_string) in [
+        (
+            OpenJdkArtifactRequirementSource::JavaVersionFile,
+            java_version_file_version as fn(&str) -> Option<String>,
+        ),
+        (OpenJdkArtifactRequirementSource::SdkmanRc, sdkmanrc_version),
+        (
+            OpenJdkArtifactRequirementSource::ToolVersions,
+            tool_versions_version,
+        ),
+    ] {
+        let version_string =
+            none_on_not_found(std::fs::read_to_string(app_dir.join(source.to_string())))
+                .map_err(|error| VersionResolveError::ReadVersionFileError(source, error))?
+                .and_then(|contents| extract_version_string(&contents));
+
+        if let Some(version_string) = version_string {
+            result.push((source, version_string));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Extracts the version from a jenv `.java-version` file which contains only the version.
+fn java_version_file_version(contents: &str) -> Option<String> {
+    significant_lines(contents).next().map(String::from)
+}
+
+/// Extracts the version from the `java=...` entry of a SDKMAN! `.sdkmanrc` file.
+fn sdkmanrc_version(contents: &str) -> Option<String> {
+    significant_lines(contents)
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "java")
+        .map(|(_, value)| String::from(value.trim()))
+        .filter(|value| !value.is_empty())
+}
+
+/// Extracts the first version of the `java` entry of an asdf/mise `.tool-versions` file.
+fn tool_versions_version(contents: &str) -> Option<String> {
+    significant_lines(contents)
+        .map(|line| {
+            line.split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+        })
+        .find_map(|mut elements| {
+            (elements.next() == Some("java"))
+                .then(|| elements.next().map(String::from))
+                .flatten()
+        })
+}
+
+fn significant_lines(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Rewrites tool specific version strings into the format used by `java.runtime.version`.
+///
+/// Tool version files commonly pin distributions this buildpack cannot install (i.e. `tem` in
+/// SDKMAN!). For these, only the version is used and the ignored distribution is returned
+/// alongside the normalized version string.
+fn normalize_version_string(
+    source: OpenJdkArtifactRequirementSource,
+    version: &str,
+) -> (String, Option<String>) {
+    let version = version.trim();
+
+    let (distribution, version) = match source {
+        // SDKMAN! identifiers are `$VERSION-$VENDOR`, i.e. `21.0.2-tem`.
+        OpenJdkArtifactRequirementSource::SdkmanRc => version
+            .rsplit_once('-')
+            .map_or((None, version), |(version, vendor)| (Some(vendor), version)),
+        // jenv and asdf/mise use `$DISTRIBUTION-$VERSION`, i.e. `temurin64-21.0.2` or
+        // `temurin-21.0.2+13.0.LTS`. Build metadata is not part of the inventory versions.
+        OpenJdkArtifactRequirementSource::JavaVersionFile
+        | OpenJdkArtifactRequirementSource::ToolVersions => {
+            let version = version
+                .split_once('+')
+                .map_or(version, |(version, _)| version);
+
+            version
+                .split_once('-')
+                .map_or((None, version), |(distribution, version)| {
+                    (
+                        Some(distribution.strip_suffix("64").unwrap_or(distribution)),
+                        version,
+                    )
+                })
+        }
+        OpenJdkArtifactRequirementSource::SystemProperties
+        | OpenJdkArtifactRequirementSource::BuildToolConfiguration(_)
+        | OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+        | OpenJdkArtifactRequirementSource::DefaultVersionFunctions => (None, version),
+    };
+
+    // Tools commonly use the `1.8` and `8.0.$UPDATE` forms for OpenJDK 8.
+    let version = match version.split_once('.') {
+        Some(("1", "8")) => String::from("8"),
+        Some(("8", rest)) if source != OpenJdkArtifactRequirementSource::SystemProperties => rest
+            .strip_prefix("0.")
+            .map_or_else(|| String::from(version), |update| format!("1.8.0_{update}")),
+        _ => String::from(version),
+    };
+
+    match distribution {
+        Some("zulu") => (format!("zulu-{version}"), None),
+        // Generic OpenJDK builds, as installed by package managers, do not pin a distribution.
+        None | Some("openjdk") => (version, None),
+        Some(distribution) => (version, Some(String::from(distribution))),
+    }
+}
+
 pub(crate) struct ResolveResult {
     pub(crate) requirement: OpenJdkArtifactRequirement,
     pub(crate) source: OpenJdkArtifactRequirementSource,
+    /// Lower precedence sources that configure a different OpenJDK version.
+    pub(crate) conflicting_sources: Vec<OpenJdkArtifactRequirementSource>,
+    /// Distribution configured in `source` that is not supported and was ignored.
+    pub(crate) ignored_distribution: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum VersionResolveError {
     #[error("{0:?}")]
     ReadSystemPropertiesError(ReadSystemPropertiesError),
-    #[error("{0:?}")]
-    OpenJdkArtifactRequirementParseError(OpenJdkArtifactRequirementParseError),
+    #[error("Cannot read {0}: {1}")]
+    ReadVersionFileError(OpenJdkArtifactRequirementSource, std::io::Error),
+    #[error("{1:?}")]
+    OpenJdkArtifactRequirementParseError(
+        OpenJdkArtifactRequirementSource,
+        OpenJdkArtifactRequirementParseError,
+    ),
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum OpenJdkArtifactRequirementSource {
     SystemProperties,
+    JavaVersionFile,
+    SdkmanRc,
+    ToolVersions,
+    /// Version inferred from the build tool configuration file with the given name.
+    BuildToolConfiguration(&'static str),
     DefaultVersionLatestLts,
     DefaultVersionFunctions,
 }
+
+impl Display for OpenJdkArtifactRequirementSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenJdkArtifactRequirementSource::SystemProperties => write!(f, "system.properties"),
+            OpenJdkArtifactRequirementSource::JavaVersionFile => write!(f, ".java-version"),
+            OpenJdkArtifactRequirementSource::SdkmanRc => write!(f, ".sdkmanrc"),
+            OpenJdkArtifactRequirementSource::ToolVersions => write!(f, ".tool-versions"),
+            OpenJdkArtifactRequirementSource::BuildToolConfiguration(file_name) => {
+                write!(f, "{file_name}")
+            }
+            OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+            | OpenJdkArtifactRequirementSource::DefaultVersionFunctions => {
+                write!(f, "buildpack default")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn version_file_precedence() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(app_dir.path().join(".tool-versions"), "java zulu-17.0.9\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::ToolVersions
+        );
+        assert_eq!(result.conflicting_sources, vec![]);
+
+        std::fs::write(app_dir.path().join(".sdkmanrc"), "java=17.0.9-zulu\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(result.source, OpenJdkArtifactRequirementSource::SdkmanRc);
+        assert_eq!(result.conflicting_sources, vec![]);
+
+        std::fs::write(app_dir.path().join(".java-version"), "21\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::JavaVersionFile
+        );
+        assert_eq!(
+            result.conflicting_sources,
+            vec![
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                OpenJdkArtifactRequirementSource::ToolVersions
+            ]
+        );
+
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.version=21\n",
+        )
+        .unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::SystemProperties
+        );
+        assert_eq!(
+            result.requirement,
+            OpenJdkArtifactRequirement {
+                version: HerokuOpenJdkVersionRequirement::Major(21),
+                distribution: OpenJdkDistribution::AzulZulu,
+            }
+        );
+        assert_eq!(
+            result.conflicting_sources,
+            vec![
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                OpenJdkArtifactRequirementSource::ToolVersions
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_lower_precedence_source_is_ignored() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(app_dir.path().join(".java-version"), "17").unwrap();
+        std::fs::write(app_dir.path().join(".sdkmanrc"), "java=latest-zulu").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::JavaVersionFile
+        );
+        assert_eq!(result.conflicting_sources, vec![]);
+
+        std::fs::remove_file(app_dir.path().join(".java-version")).unwrap();
+
+        assert!(matches!(
+            resolve_version(app_dir.path()),
+            Err(VersionResolveError::OpenJdkArtifactRequirementParseError(
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                OpenJdkArtifactRequirementParseError::OpenJdkVersionParseError(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn inferred_version() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("pom.xml"),
+            "<project><properties><maven.compiler.release>17</maven.compiler.release></properties></project>",
+        )
+        .unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::BuildToolConfiguration("pom.xml")
+        );
+        assert_eq!(
+            result.requirement.version,
+            HerokuOpenJdkVersionRequirement::Major(17)
+        );
+
+        // Explicitly configured versions always take precedence
+        std::fs::write(app_dir.path().join(".java-version"), "21").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::JavaVersionFile
+        );
+    }
+
+    #[test]
+    fn default_version() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(app_dir.path().join(".tool-versions"), "nodejs 22.1.0\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::DefaultVersionLatestLts
+        );
+    }
+
+    #[test]
+    fn extract_version_strings() {
+        assert_eq!(
+            java_version_file_version("\n  temurin64-21.0.2  \n"),
+            Some(String::from("temurin64-21.0.2"))
+        );
+        assert_eq!(java_version_file_version("\n"), None);
+
+        assert_eq!(
+            sdkmanrc_version("# Enable auto-env\nmaven=3.9.6\njava = 21.0.2-tem\n"),
+            Some(String::from("21.0.2-tem"))
+        );
+        assert_eq!(sdkmanrc_version("#java=21.0.2-tem\n"), None);
+
+        assert_eq!(
+            tool_versions_version(
+                "nodejs 22.1.0\njava temurin-21.0.2+13.0.LTS corretto-21.0.2.13.1 # comment\n"
+            ),
+            Some(String::from("temurin-21.0.2+13.0.LTS"))
+        );
+        assert_eq!(tool_versions_version("java\n"), None);
+        assert_eq!(tool_versions_version("javascript 1\n"), None);
+    }
+
+    #[test]
+    fn unsupported_tool_file_distributions_are_ignored() {
+        let app_dir = tempdir().unwrap();
+        std::fs::write(app_dir.path().join(".sdkmanrc"), "java=21.0.2-tem\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(result.source, OpenJdkArtifactRequirementSource::SdkmanRc);
+        assert_eq!(
+            result.requirement,
+            "21.0.2".parse::<OpenJdkArtifactRequirement>().unwrap()
+        );
+        assert_eq!(result.ignored_distribution, Some(String::from("tem")));
+
+        std::fs::remove_file(app_dir.path().join(".sdkmanrc")).unwrap();
+        std::fs::write(app_dir.path().join(".tool-versions"), "java temurin-21\n").unwrap();
+
+        let result = resolve_version(app_dir.path()).unwrap();
+        assert_eq!(
+            result.source,
+            OpenJdkArtifactRequirementSource::ToolVersions
+        );
+        assert_eq!(
+            result.requirement,
+            OpenJdkArtifactRequirement {
+                version: HerokuOpenJdkVersionRequirement::Major(21),
+                distribution: OpenJdkDistribution::AzulZulu,
+            }
+        );
+        assert_eq!(result.ignored_distribution, Some(String::from("temurin")));
+
+        // Distributions in system.properties are never ignored.
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.version=temurin-21\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            resolve_version(app_dir.path()),
+            Err(VersionResolveError::OpenJdkArtifactRequirementParseError(
+                OpenJdkArtifactRequirementSource::SystemProperties,
+                OpenJdkArtifactRequirementParseError::UnknownDistribution(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn normalize_version_strings() {
+        for (source, input, expected, expected_ignored_distribution) in [
+            (
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                "21.0.2-tem",
+                "21.0.2",
+                Some("tem"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                "17.0.10-amzn",
+                "17.0.10",
+                Some("amzn"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                "21.0.2-zulu",
+                "zulu-21.0.2",
+                None,
+            ),
+            (
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                "8.0.402-tem",
+                "1.8.0_402",
+                Some("tem"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::SdkmanRc,
+                "21.0.2-graalce",
+                "21.0.2",
+                Some("graalce"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::ToolVersions,
+                "temurin-21.0.2+13.0.LTS",
+                "21.0.2",
+                Some("temurin"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::ToolVersions,
+                "openjdk-21",
+                "21",
+                None,
+            ),
+            (
+                OpenJdkArtifactRequirementSource::ToolVersions,
+                "17",
+                "17",
+                None,
+            ),
+            (
+                OpenJdkArtifactRequirementSource::JavaVersionFile,
+                "1.8",
+                "8",
+                None,
+            ),
+            (
+                OpenJdkArtifactRequirementSource::JavaVersionFile,
+                "temurin64-21.0.2",
+                "21.0.2",
+                Some("temurin"),
+            ),
+            (
+                OpenJdkArtifactRequirementSource::JavaVersionFile,
+                "openjdk64-17.0.2",
+                "17.0.2",
+                None,
+            ),
+            (
+                OpenJdkArtifactRequirementSource::SystemProperties,
+                "zulu-1.8.0_402",
+                "zulu-1.8.0_402",
+                None,
+            ),
+        ] {
+            assert_eq!(
+                normalize_version_string(source, input),
+                (
+                    String::from(expected),
+                    expected_ignored_distribution.map(String::from)
+                )
+            );
+        }
+    }
+}
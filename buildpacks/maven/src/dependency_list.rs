@@ -0,0 +1,78 @@
+use buildpacks_jvm_shared::sbom::MavenArtifact;
+
+/// Parses the output of Maven's `dependency:list` goal into the artifacts that are part of the
+/// application at runtime. Artifacts in the `test` and `provided` scopes are not included.
+///
+/// Each dependency is listed on its own line, in the format
+/// `groupId:artifactId:type[:classifier]:version:scope`, optionally followed by additional
+/// information such as ` -- module name` or ` (optional)`.
+pub(crate) fn parse_runtime_artifacts(dependency_list: &str) -> Vec<MavenArtifact> {
+    dependency_list
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter_map(|coordinates| {
+            let elements = coordinates.split(':').collect::<Vec<_>>();
+
+            let (classifier, version, scope) = match elements.as_slice() {
+                [_, _, _, version, scope] => (None, *version, *scope),
+                [_, _, _, classifier, version, scope] => (Some(*classifier), *version, *scope),
+                _ => return None,
+            };
+
+            RUNTIME_SCOPES.contains(&scope).then(|| MavenArtifact {
+                group_id: String::from(elements[0]),
+                artifact_id: String::from(elements[1]),
+                version: String::from(version),
+                artifact_type: String::from(elements[2]),
+                classifier: classifier.map(String::from),
+            })
+        })
+        .collect()
+}
+
+const RUNTIME_SCOPES: [&str; 3] = ["compile", "runtime", "system"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_dependency_list() {
+        let artifacts = parse_runtime_artifacts(indoc! {"
+
+            The following files have been resolved:
+               io.undertow:undertow-core:jar:2.3.21.Final:compile -- module undertow.core
+               org.jboss.xnio:xnio-nio:jar:3.8.16.Final:runtime
+               io.netty:netty-transport-native-epoll:jar:linux-x86_64:4.1.100.Final:compile (optional)
+               jakarta.servlet:jakarta.servlet-api:jar:6.0.0:provided
+               junit:junit:jar:4.13.1:test
+
+        "});
+
+        assert_eq!(
+            artifacts
+                .iter()
+                .map(MavenArtifact::purl)
+                .collect::<Vec<_>>(),
+            vec![
+                "pkg:maven/io.undertow/undertow-core@2.3.21.Final",
+                "pkg:maven/org.jboss.xnio/xnio-nio@3.8.16.Final",
+                "pkg:maven/io.netty/netty-transport-native-epoll@4.1.100.Final?classifier=linux-x86_64",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_empty_dependency_list() {
+        assert_eq!(
+            parse_runtime_artifacts(indoc! {"
+
+                The following files have been resolved:
+                   none
+
+            "}),
+            vec![]
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use crate::project::{MAVEN_PROJECT_ENV_VAR_NAME, ReactorModulesError};
 use crate::{MavenBuildpackError, SettingsError};
 use buildpacks_jvm_shared as shared;
+use buildpacks_jvm_shared::framework::DefaultAppProcessError;
 use indoc::formatdoc;
 
 #[allow(clippy::too_many_lines)]
@@ -7,6 +9,9 @@ pub(crate) fn on_error_maven_buildpack(error: MavenBuildpackError) {
     match error {
         MavenBuildpackError::DetermineModeError(
             shared::system_properties::ReadSystemPropertiesError::IoError(error),
+        )
+        | MavenBuildpackError::ReadMavenProjectError(
+            shared::system_properties::ReadSystemPropertiesError::IoError(error),
         ) => shared::log::log_please_try_again_error(
             "Unexpected IO error",
             "Could not read your application's system.properties file due to an unexpected I/O error.",
@@ -40,13 +45,29 @@ pub(crate) fn on_error_maven_buildpack(error: MavenBuildpackError) {
                 error,
             );
         }
-        MavenBuildpackError::DefaultAppProcessError(error) => {
+        MavenBuildpackError::DefaultAppProcessError(DefaultAppProcessError::Io(error)) => {
             shared::log::log_please_try_again_error(
                 "Could not determine default process",
                 "While trying to determine a default process based on the used application framework, an unexpected error occurred.",
                 error,
             );
         }
+        MavenBuildpackError::ReadBooleanSettingError(error) => {
+            shared::log::log_read_boolean_setting_error(error);
+        }
+        MavenBuildpackError::SpringBootLayersError(error) => {
+            shared::log::log_spring_boot_layers_error(error);
+        }
+        MavenBuildpackError::CannotReadJavaVersion(error) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not read the version of the installed OpenJDK due to an unexpected I/O error.",
+                error,
+            );
+        }
+        MavenBuildpackError::ProcessDefinitionsError(error) => {
+            shared::log::log_process_definitions_error(error);
+        }
         MavenBuildpackError::UnsupportedMavenVersion(version) => shared::output::print_error(
             "Unsupported Maven version",
             formatdoc! {"
@@ -119,6 +140,9 @@ pub(crate) fn on_error_maven_buildpack(error: MavenBuildpackError) {
         ),
         MavenBuildpackError::DetermineModeError(
             shared::system_properties::ReadSystemPropertiesError::ParseError(error),
+        )
+        | MavenBuildpackError::ReadMavenProjectError(
+            shared::system_properties::ReadSystemPropertiesError::ParseError(error),
         ) => shared::output::print_error(
             "Invalid system.properties file",
             formatdoc! {"
@@ -128,5 +152,63 @@ pub(crate) fn on_error_maven_buildpack(error: MavenBuildpackError) {
                 Details: {error}
             ", error = error },
         ),
+        MavenBuildpackError::ReadRuntimeImageConfigError(error) => {
+            shared::log::log_runtime_image_config_error(error);
+        }
+        MavenBuildpackError::CannotCreateRuntimeImage(error) => {
+            shared::log::log_runtime_image_error(error);
+        }
+        MavenBuildpackError::NativeImageError(error) => {
+            shared::log::log_native_image_error(error);
+        }
+        MavenBuildpackError::CannotReadMavenDependencyList(error) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not read the Maven dependency list to create the application SBOM.",
+                error,
+            );
+        }
+
+        MavenBuildpackError::ReactorModulesError(ReactorModulesError::Io(error)) => {
+            shared::log::log_please_try_again_error(
+                "Unexpected IO error",
+                "Could not determine the modules of your Maven project due to an unexpected I/O error.",
+                error,
+            );
+        }
+        MavenBuildpackError::ReactorModulesError(ReactorModulesError::InvalidPom(path, error)) => {
+            shared::output::print_error(
+                "Invalid pom.xml file",
+                formatdoc! {"
+                    Could not parse {path} to determine the modules of your Maven project.
+                    Please ensure that the file is valid XML and try again.
+
+                    Details: {error}
+                ", path = path.to_string_lossy() },
+            );
+        }
+        MavenBuildpackError::UnknownMavenProject {
+            maven_project,
+            reactor_modules,
+        } => shared::output::print_error(
+            "Unknown Maven project",
+            formatdoc! {"
+                You have set {MAVEN_PROJECT_ENV_VAR_NAME} or 'maven.project' in 'system.properties' to \"{maven_project}\",
+                but your Maven project does not contain a module at that path.
+
+                Available modules: {reactor_modules}
+            ",
+                maven_project = maven_project.to_string_lossy(),
+                reactor_modules = if reactor_modules.is_empty() {
+                    String::from("<none>")
+                } else {
+                    reactor_modules
+                        .iter()
+                        .map(|module| module.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            },
+        ),
     }
 }
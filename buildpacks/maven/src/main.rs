@@ -3,17 +3,53 @@
 // to be able selectively opt out of coverage for functions/lines/modules.
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+use crate::dependency_list::parse_runtime_artifacts;
 use crate::errors::on_error_maven_buildpack;
-use crate::framework::DefaultAppProcessError;
 use crate::layer::maven::handle_maven_layer;
 use crate::layer::maven_repo::handle_maven_repository_layer;
 use crate::mode::{Mode, determine_mode};
+use crate::project::{
+    ApplicationTargetDir, ReactorModulesError, application_target_dir, reactor_modules,
+    read_maven_project,
+};
 use crate::settings::{SettingsError, resolve_settings_xml_path};
-use crate::warnings::{log_default_maven_version_warning, log_unused_maven_wrapper_warning};
-use buildpacks_jvm_shared::system_properties::ReadSystemPropertiesError;
+use crate::warnings::{
+    log_ambiguous_application_module_warning, log_default_maven_version_warning,
+    log_unused_maven_wrapper_warning,
+};
+use buildpacks_jvm_shared::cds::read_cds_config;
+use buildpacks_jvm_shared::framework::{
+    BuildOutputDirs, DefaultAppProcessError, Framework, LaunchTarget, default_app_process,
+    detect_framework, find_launch_target,
+};
+use buildpacks_jvm_shared::layers::cds::{ReadJavaVersionError, handle_cds_layer};
+use buildpacks_jvm_shared::layers::class_count::handle_class_count_layer;
+use buildpacks_jvm_shared::layers::framework::handle_framework_layer;
+use buildpacks_jvm_shared::layers::jre::handle_jlink_jre_layer;
+use buildpacks_jvm_shared::layers::native_image::handle_native_image_layer;
+use buildpacks_jvm_shared::layers::spring_boot::handle_spring_boot_layers;
+use buildpacks_jvm_shared::log::{
+    log_cds_unsupported_warning, log_spring_boot_jar_not_layered_warning,
+};
+use buildpacks_jvm_shared::native_image::{NativeImageError, ensure_native_image_available};
+use buildpacks_jvm_shared::processes::{
+    ProcessDefinitionsError, additional_processes, read_process_definitions,
+};
+use buildpacks_jvm_shared::runtime_image::{
+    RuntimeImageConfigError, RuntimeImageError, RuntimeImageType, jdk_requirement,
+    read_runtime_image_config,
+};
+use buildpacks_jvm_shared::sbom::maven_artifacts_cyclonedx_sbom;
+use buildpacks_jvm_shared::spring_boot::{
+    SpringBootLayersError, layered_jar_start_class, read_extract_layers_config,
+};
+use buildpacks_jvm_shared::system_properties::{
+    ReadBooleanSettingError, ReadSystemPropertiesError,
+};
 use libcnb::build::{BuildContext, BuildResult, BuildResultBuilder};
 use libcnb::data::build_plan::BuildPlanBuilder;
-use libcnb::data::launch::{LaunchBuilder, ProcessBuilder};
+use libcnb::data::launch::LaunchBuilder;
+use libcnb::data::process_type;
 use libcnb::detect::{DetectContext, DetectResult, DetectResultBuilder};
 use libcnb::generic::GenericPlatform;
 use libcnb::{Buildpack, Env, Error, Platform, buildpack_main};
@@ -37,10 +73,11 @@ use java_properties as _;
 #[cfg(test)]
 use libcnb_test as _;
 
+mod dependency_list;
 mod errors;
-mod framework;
 mod layer;
 mod mode;
+mod project;
 mod settings;
 mod util;
 mod warnings;
@@ -66,6 +103,20 @@ enum MavenBuildpackError {
     MavenBuildIoError(std::io::Error),
     CannotSetMavenWrapperExecutableBit(std::io::Error),
     DefaultAppProcessError(DefaultAppProcessError),
+    ProcessDefinitionsError(ProcessDefinitionsError),
+    ReadRuntimeImageConfigError(RuntimeImageConfigError),
+    CannotCreateRuntimeImage(RuntimeImageError),
+    NativeImageError(NativeImageError),
+    ReadBooleanSettingError(ReadBooleanSettingError),
+    SpringBootLayersError(SpringBootLayersError),
+    CannotReadJavaVersion(std::io::Error),
+    CannotReadMavenDependencyList(std::io::Error),
+    ReadMavenProjectError(ReadSystemPropertiesError),
+    ReactorModulesError(ReactorModulesError),
+    UnknownMavenProject {
+        maven_project: PathBuf,
+        reactor_modules: Vec<PathBuf>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -96,7 +147,7 @@ impl Buildpack for MavenBuildpack {
             DetectResultBuilder::pass()
                 .build_plan(
                     BuildPlanBuilder::new()
-                        .requires("jdk")
+                        .requires(jdk_requirement())
                         .provides("jvm-application")
                         .requires("jvm-application")
                         .build(),
@@ -179,10 +230,28 @@ impl Buildpack for MavenBuildpack {
                 mvn_env.insert("JAVA_HOME", java_home);
             }
 
+            let runtime_image_config =
+                read_runtime_image_config(&context.app_dir, context.platform.env())
+                    .map_err(MavenBuildpackError::ReadRuntimeImageConfigError)?;
+
+            let native = runtime_image_config.image_type == RuntimeImageType::Native;
+            if native {
+                ensure_native_image_available(
+                    current_or_platform_env.get("JAVA_HOME").map(Path::new),
+                )
+                .map_err(MavenBuildpackError::NativeImageError)?;
+            }
+
             let maven_goals = current_or_platform_env
                 .get("MAVEN_CUSTOM_GOALS")
                 .map_or_else(
-                    || Ok(default_maven_goals()),
+                    || {
+                        Ok(if native {
+                            default_native_maven_goals()
+                        } else {
+                            default_maven_goals()
+                        })
+                    },
                     |maven_custom_goals_string| {
                         shell_words::split(&maven_custom_goals_string.to_string_lossy())
                             .map_err(MavenBuildpackError::CannotSplitMavenCustomGoals)
@@ -211,6 +280,32 @@ impl Buildpack for MavenBuildpack {
                 maven_options.push(settings_xml_path.to_string_lossy().to_string());
             }
 
+            let maven_project = read_maven_project(&context.app_dir, &current_or_platform_env)
+                .map_err(MavenBuildpackError::ReadMavenProjectError)?;
+
+            if let Some(maven_project) = &maven_project {
+                let reactor_modules = reactor_modules(&context.app_dir)
+                    .map_err(MavenBuildpackError::ReactorModulesError)?;
+
+                if !reactor_modules.contains(maven_project) {
+                    Err(MavenBuildpackError::UnknownMavenProject {
+                        maven_project: maven_project.clone(),
+                        reactor_modules,
+                    })?;
+                }
+
+                // Builds the selected module and the modules it depends on.
+                maven_options.push(String::from("-pl"));
+                maven_options.push(maven_project.to_string_lossy().to_string());
+                maven_options.push(String::from("-am"));
+            }
+
+            // The native profile is used by Spring Boot's parent POM and the GraalVM Native Build
+            // Tools documentation to configure the native build.
+            if native {
+                maven_options.push(String::from("-Pnative"));
+            }
+
             // We need to set some options that relate to buildpack implementation internals. Those
             // options must not be overridden by the user via MAVEN_CUSTOM_OPTS for the buildpack to
             // work correctly. We also don't want to show them when we log the Maven command we're
@@ -266,10 +361,9 @@ impl Buildpack for MavenBuildpack {
                     .args(
                         maven_options.iter().chain(&internal_maven_options).chain(
                             [
-                                format!(
-                                    "-DoutputFile={}",
-                                    app_dependency_list_path(&context.app_dir).to_string_lossy()
-                                ),
+                                // Relative paths are resolved against the directory of each
+                                // reactor module, every module gets its own dependency list.
+                                format!("-DoutputFile=target/{DEPENDENCY_LIST_FILE_NAME}"),
                                 String::from("dependency:list"),
                             ]
                             .iter(),
@@ -285,15 +379,116 @@ impl Buildpack for MavenBuildpack {
                 )
             })?;
 
-            let mut build_result_builder = BuildResultBuilder::new();
+            let application_target_dir =
+                application_target_dir(&context.app_dir, maven_project.as_deref())
+                    .map_err(MavenBuildpackError::ReactorModulesError)?;
+
+            if let ApplicationTargetDir::Ambiguous { modules, .. } = &application_target_dir {
+                log_ambiguous_application_module_warning(modules);
+            }
+
+            if runtime_image_config.image_type == RuntimeImageType::Jlink {
+                handle_jlink_jre_layer(
+                    &context,
+                    current_or_platform_env.get("JAVA_HOME").map(Path::new),
+                    application_target_dir.path(),
+                    &runtime_image_config,
+                )?;
+            }
+
+            let runtime_artifacts = fs::read_to_string(
+                application_target_dir
+                    .path()
+                    .join(DEPENDENCY_LIST_FILE_NAME),
+            )
+            .map(|dependency_list| parse_runtime_artifacts(&dependency_list))
+            .map_err(MavenBuildpackError::CannotReadMavenDependencyList)?;
+
+            let mut build_result_builder = BuildResultBuilder::new()
+                .launch_sbom(maven_artifacts_cyclonedx_sbom(&runtime_artifacts));
+
+            let target_dir = application_target_dir.path();
+
+            let framework = detect_framework(&runtime_artifacts);
+            if let Some(framework) = framework {
+                handle_framework_layer(&context, framework)?;
+            }
 
-            if let Some(process) = framework::default_app_process(&context.app_dir)
+            let mut launch_target = if native {
+                Some(handle_native_image_layer(&context, target_dir)?)
+            } else {
+                find_launch_target(
+                    framework,
+                    &BuildOutputDirs {
+                        jars: target_dir.to_path_buf(),
+                        quarkus_app: target_dir.join("quarkus-app"),
+                        install: None,
+                    },
+                )
                 .map_err(MavenBuildpackError::DefaultAppProcessError)?
+            };
+
+            if framework == Some(Framework::SpringBoot)
+                && let Some(LaunchTarget::Jar(jar_path)) = &launch_target
+                && read_extract_layers_config(&context.app_dir, context.platform.env())
+                    .map_err(MavenBuildpackError::ReadBooleanSettingError)?
             {
-                build_result_builder =
-                    build_result_builder.launch(LaunchBuilder::new().process(process).build());
+                match layered_jar_start_class(jar_path).map_err(|error| {
+                    MavenBuildpackError::SpringBootLayersError(SpringBootLayersError::Io(error))
+                })? {
+                    Some(start_class) => {
+                        launch_target = Some(handle_spring_boot_layers(
+                            &context,
+                            &current_or_platform_env,
+                            jar_path,
+                            &start_class,
+                        )?);
+                    }
+                    None => log_spring_boot_jar_not_layered_warning(jar_path),
+                }
             }
 
+            if read_cds_config(&context.app_dir, context.platform.env())
+                .map_err(MavenBuildpackError::ReadBooleanSettingError)?
+            {
+                match &launch_target {
+                    Some(launch_target)
+                        if framework == Some(Framework::SpringBoot)
+                            && runtime_image_config.image_type == RuntimeImageType::Jdk =>
+                    {
+                        handle_cds_layer(&context, &current_or_platform_env, launch_target)?;
+                    }
+                    _ => log_cds_unsupported_warning(),
+                }
+            }
+
+            if let Some(launch_target) = &launch_target {
+                handle_class_count_layer(&context, launch_target)?;
+            }
+
+            let process_definitions = read_process_definitions(&context.app_dir)
+                .map_err(MavenBuildpackError::ProcessDefinitionsError)?;
+
+            let mut launch_builder = LaunchBuilder::new();
+
+            // A `web` process defined in `project.toml` replaces the default process.
+            if !process_definitions.contains_key(&process_type!("web"))
+                && let Some(launch_target) = &launch_target
+            {
+                launch_builder.process(default_app_process(
+                    framework,
+                    launch_target,
+                    target_dir.parent().unwrap_or(&context.app_dir),
+                ));
+            }
+
+            launch_builder.processes(
+                additional_processes(&process_definitions, launch_target.as_ref())
+                    .map_err(MavenBuildpackError::ProcessDefinitionsError)?,
+            );
+
+            build_result_builder = build_result_builder.launch(launch_builder.build());
+
             build_result_builder.build()
         })
     }
@@ -311,14 +506,41 @@ impl From<MavenBuildpackError> for libcnb::Error<MavenBuildpackError> {
     }
 }
 
-fn app_dependency_list_path<P: AsRef<Path>>(app_dir: P) -> PathBuf {
-    app_dir.as_ref().join("target/mvn-dependency-list.log")
+impl From<ReadJavaVersionError> for MavenBuildpackError {
+    fn from(e: ReadJavaVersionError) -> Self {
+        MavenBuildpackError::CannotReadJavaVersion(e.0)
+    }
+}
+
+impl From<NativeImageError> for MavenBuildpackError {
+    fn from(e: NativeImageError) -> Self {
+        MavenBuildpackError::NativeImageError(e)
+    }
+}
+
+impl From<SpringBootLayersError> for MavenBuildpackError {
+    fn from(e: SpringBootLayersError) -> Self {
+        MavenBuildpackError::SpringBootLayersError(e)
+    }
+}
+
+impl From<RuntimeImageError> for MavenBuildpackError {
+    fn from(e: RuntimeImageError) -> Self {
+        MavenBuildpackError::CannotCreateRuntimeImage(e)
+    }
 }
 
+const DEPENDENCY_LIST_FILE_NAME: &str = "mvn-dependency-list.log";
+
 fn default_maven_goals() -> Vec<String> {
     vec![String::from("clean"), String::from("install")]
 }
 
+// The native:compile goal forks the package phase before compiling the native executable.
+fn default_native_maven_goals() -> Vec<String> {
+    vec![String::from("clean"), String::from("native:compile")]
+}
+
 fn default_maven_opts() -> Vec<String> {
     vec![String::from("-DskipTests")]
 }
@@ -0,0 +1,321 @@
+use buildpacks_jvm_shared::framework::{candidate_jars, find_executable_jars};
+use buildpacks_jvm_shared::result::none_on_not_found;
+use buildpacks_jvm_shared::system_properties::{ReadSystemPropertiesError, read_system_properties};
+use libcnb::Env;
+use std::path::{Path, PathBuf};
+
+/// Reads the reactor module the application should be built from. The `MAVEN_PROJECT`
+/// environment variable takes precedence over `maven.project` in `system.properties`.
+///
+/// The module is given as a path relative to the application directory, i.e. `service-api`.
+pub(crate) fn read_maven_project(
+    app_dir: &Path,
+    env: &Env,
+) -> Result<Option<PathBuf>, ReadSystemPropertiesError> {
+    let maven_project = match env.get(MAVEN_PROJECT_ENV_VAR_NAME) {
+        Some(value) => Some(value.to_string_lossy().to_string()),
+        None => read_system_properties(app_dir)?
+            .get(MAVEN_PROJECT_PROPERTY_NAME)
+            .cloned(),
+    };
+
+    Ok(maven_project
+        .map(|maven_project| String::from(maven_project.trim().trim_matches('/')))
+        .filter(|maven_project| !maven_project.is_empty())
+        .map(PathBuf::from))
+}
+
+/// Lists the modules of the Maven reactor, including nested modules, as paths relative to the
+/// application directory. Applications without a `pom.xml` or `<modules>` have no modules.
+pub(crate) fn reactor_modules(app_dir: &Path) -> Result<Vec<PathBuf>, ReactorModulesError> {
+    let mut modules = Vec::new();
+    collect_reactor_modules(app_dir, Path::new(""), &mut modules)?;
+    Ok(modules)
+}
+
+fn collect_reactor_modules(
+    app_dir: &Path,
+    module: &Path,
+    acc: &mut Vec<PathBuf>,
+) -> Result<(), ReactorModulesError> {
+    let pom_path = app_dir.join(module).join("pom.xml");
+
+    let Some(pom) =
+        none_on_not_found(std::fs::read_to_string(&pom_path)).map_err(ReactorModulesError::Io)?
+    else {
+        return Ok(());
+    };
+
+    let submodules = parse_pom_modules(&pom)
+        .map_err(|error| ReactorModulesError::InvalidPom(pom_path, error))?;
+
+    for submodule in submodules {
+        // Modules can also reference a POM file instead of a directory.
+        let submodule = Path::new(&submodule);
+        let submodule = if submodule
+            .extension()
+            .is_some_and(|extension| extension == "xml")
+        {
+            submodule.parent().unwrap_or(submodule)
+        } else {
+            submodule
+        };
+
+        let submodule = module.join(submodule);
+        acc.push(submodule.clone());
+        collect_reactor_modules(app_dir, &submodule, acc)?;
+    }
+
+    Ok(())
+}
+
+fn parse_pom_modules(pom: &str) -> Result<Vec<String>, roxmltree::Error> {
+    let document = roxmltree::Document::parse(pom)?;
+
+    Ok(document
+        .root_element()
+        .children()
+        .filter(|node| node.tag_name().name() == "modules")
+        .flat_map(|modules| modules.children())
+        .filter(|node| node.tag_name().name() == "module")
+        .filter_map(|module| module.text())
+        .map(|module| String::from(module.trim()))
+        .filter(|module| !module.is_empty())
+        .collect())
+}
+
+/// Determines the `target` directory of the application that should be launched.
+///
+/// If a Maven project has been configured, its `target` directory is used. Otherwise, the `target`
+/// directory of the root project is used, unless it contains no JAR file and exactly one reactor
+/// module built an executable JAR file.
+pub(crate) fn application_target_dir(
+    app_dir: &Path,
+    maven_project: Option<&Path>,
+) -> Result<ApplicationTargetDir, ReactorModulesError> {
+    let root_target_dir = app_dir.join("target");
+
+    if let Some(maven_project) = maven_project {
+        return Ok(ApplicationTargetDir::Module(
+            app_dir.join(maven_project).join("target"),
+        ));
+    }
+
+    if !candidate_jars(&root_target_dir)
+        .map_err(ReactorModulesError::Io)?
+        .is_empty()
+    {
+        return Ok(ApplicationTargetDir::Root(root_target_dir));
+    }
+
+    let mut executable_modules = Vec::new();
+    for module in reactor_modules(app_dir)? {
+        let module_target_dir = app_dir.join(&module).join("target");
+
+        if !find_executable_jars(&module_target_dir)
+            .map_err(ReactorModulesError::Io)?
+            .is_empty()
+        {
+            executable_modules.push((module, module_target_dir));
+        }
+    }
+
+    Ok(match executable_modules.as_slice() {
+        [] => ApplicationTargetDir::Root(root_target_dir),
+        [(_, module_target_dir)] => ApplicationTargetDir::Module(module_target_dir.clone()),
+        _ => ApplicationTargetDir::Ambiguous {
+            root_target_dir,
+            modules: executable_modules
+                .into_iter()
+                .map(|(module, _)| module)
+                .collect(),
+        },
+    })
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum ApplicationTargetDir {
+    Root(PathBuf),
+    Module(PathBuf),
+    /// Several reactor modules built an executable JAR file. The root `target` directory is used.
+    Ambiguous {
+        root_target_dir: PathBuf,
+        modules: Vec<PathBuf>,
+    },
+}
+
+impl ApplicationTargetDir {
+    pub(crate) fn path(&self) -> &Path {
+        match self {
+            ApplicationTargetDir::Root(path)
+            | ApplicationTargetDir::Module(path)
+            | ApplicationTargetDir::Ambiguous {
+                root_target_dir: path,
+                ..
+            } => path,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ReactorModulesError {
+    Io(std::io::Error),
+    InvalidPom(PathBuf, roxmltree::Error),
+}
+
+pub(crate) const MAVEN_PROJECT_ENV_VAR_NAME: &str = "MAVEN_PROJECT";
+const MAVEN_PROJECT_PROPERTY_NAME: &str = "maven.project";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    const PARENT_POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.0.0</version>
+    <packaging>pom</packaging>
+    <modules>
+        <!-- <module>disabled</module> -->
+        <module>shared</module>
+        <module>service-api</module>
+    </modules>
+    <profiles>
+        <profile>
+            <id>extras</id>
+            <modules>
+                <module>extras</module>
+            </modules>
+        </profile>
+    </profiles>
+</project>
+"#;
+
+    const SHARED_POM: &str = r#"<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <modules>
+        <module>shared-core/pom.xml</module>
+    </modules>
+</project>
+"#;
+
+    fn write_jar(path: &Path, manifest: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        writer
+            .start_file(
+                "META-INF/MANIFEST.MF",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(manifest.as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn multi_module_app() -> tempfile::TempDir {
+        let app_dir = tempfile::tempdir().unwrap();
+        fs::write(app_dir.path().join("pom.xml"), PARENT_POM).unwrap();
+        fs::create_dir_all(app_dir.path().join("shared/shared-core")).unwrap();
+        fs::write(app_dir.path().join("shared/pom.xml"), SHARED_POM).unwrap();
+        app_dir
+    }
+
+    #[test]
+    fn reactor_modules_of_multi_module_project() {
+        let app_dir = multi_module_app();
+
+        assert_eq!(
+            reactor_modules(app_dir.path()).unwrap(),
+            vec![
+                PathBuf::from("shared"),
+                PathBuf::from("shared/shared-core"),
+                PathBuf::from("service-api"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reactor_modules_without_pom() {
+        let app_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            reactor_modules(app_dir.path()).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn read_maven_project_from_env_and_system_properties() {
+        let app_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            read_maven_project(app_dir.path(), &Env::new()).unwrap(),
+            None
+        );
+
+        fs::write(
+            app_dir.path().join("system.properties"),
+            "maven.project=service-api/\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_maven_project(app_dir.path(), &Env::new()).unwrap(),
+            Some(PathBuf::from("service-api"))
+        );
+
+        let mut env = Env::new();
+        env.insert(MAVEN_PROJECT_ENV_VAR_NAME, "shared/shared-core");
+
+        assert_eq!(
+            read_maven_project(app_dir.path(), &env).unwrap(),
+            Some(PathBuf::from("shared/shared-core"))
+        );
+    }
+
+    #[test]
+    fn application_target_dir_of_multi_module_project() {
+        let app_dir = multi_module_app();
+
+        write_jar(
+            &app_dir.path().join("shared/target/shared-1.0.0.jar"),
+            "Manifest-Version: 1.0\n",
+        );
+        write_jar(
+            &app_dir
+                .path()
+                .join("service-api/target/service-api-1.0.0.jar"),
+            "Manifest-Version: 1.0\nMain-Class: org.springframework.boot.loader.launch.JarLauncher\n",
+        );
+
+        assert_eq!(
+            application_target_dir(app_dir.path(), None).unwrap(),
+            ApplicationTargetDir::Module(app_dir.path().join("service-api/target"))
+        );
+
+        assert_eq!(
+            application_target_dir(app_dir.path(), Some(Path::new("shared"))).unwrap(),
+            ApplicationTargetDir::Module(app_dir.path().join("shared/target"))
+        );
+
+        write_jar(
+            &app_dir
+                .path()
+                .join("shared/shared-core/target/shared-core-1.0.0.jar"),
+            "Manifest-Version: 1.0\nMain-Class: com.example.Cli\n",
+        );
+
+        assert_eq!(
+            application_target_dir(app_dir.path(), None).unwrap(),
+            ApplicationTargetDir::Ambiguous {
+                root_target_dir: app_dir.path().join("target"),
+                modules: vec![
+                    PathBuf::from("shared/shared-core"),
+                    PathBuf::from("service-api")
+                ],
+            }
+        );
+    }
+}
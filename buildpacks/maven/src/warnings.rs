@@ -1,5 +1,6 @@
 use indoc::formatdoc;
 use libherokubuildpack::log::log_warning;
+use std::path::PathBuf;
 
 pub(crate) fn log_unused_maven_wrapper_warning(version: &str) {
     log_warning(
@@ -25,3 +26,16 @@ pub(crate) fn log_default_maven_version_warning(version: &str) {
         ", version = version },
     );
 }
+
+pub(crate) fn log_ambiguous_application_module_warning(modules: &[PathBuf]) {
+    log_warning(
+        "Multiple executable modules",
+        formatdoc! {"
+            The following modules of your Maven project all contain an executable JAR file: {modules}
+            We cannot determine which one should be launched and will not create a default process.
+
+            Set MAVEN_PROJECT or 'maven.project' in 'system.properties' to the path of the module
+            that should be launched.
+        ", modules = modules.iter().map(|module| module.to_string_lossy()).collect::<Vec<_>>().join(", ") },
+    );
+}
@@ -0,0 +1,22 @@
+use crate::default_build_config;
+use libcnb_test::{TestRunner, assert_contains, assert_not_contains};
+
+#[test]
+#[ignore = "integration test"]
+fn cds_archive_is_accepted_at_launch() {
+    TestRunner::default().build(
+        default_build_config("test-apps/buildpack-java-spring-boot-test").env("JAVA_CDS", "true"),
+        |context| {
+            assert_contains!(context.pack_stdout, "Creating class data sharing archive");
+
+            // With -Xshare:on, the JVM refuses to start if the archive created during the build
+            // cannot be used, i.e. because the modification times of the JAR files changed when
+            // the image was exported.
+            let output = context.run_shell_command(
+                "java $JAVA_OPTS -Xshare:on -Xlog:cds -Dspring.context.exit=onRefresh -jar target/*.jar",
+            );
+
+            assert_not_contains!(output.stdout, "is not the one used while building");
+        },
+    );
+}
@@ -16,6 +16,7 @@ use std::path::Path;
 
 mod automatic_process_type;
 mod caching;
+mod cds;
 mod customization;
 mod misc;
 mod polyglot;
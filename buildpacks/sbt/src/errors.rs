@@ -21,6 +21,7 @@ pub(crate) enum SbtBuildpackError {
     SbtBuildUnexpectedExitStatus(ExitStatus, Option<SbtError>),
     ReadSbtBuildpackConfigurationError(ReadSbtBuildpackConfigurationError),
     ReadSystemPropertiesError(shared::system_properties::ReadSystemPropertiesError),
+    ProcessDefinitionsError(shared::processes::ProcessDefinitionsError),
 }
 
 #[allow(clippy::too_many_lines)]
@@ -176,6 +177,10 @@ pub(crate) fn log_user_errors(error: SbtBuildpackError) {
             "},
         ),
 
+        SbtBuildpackError::ProcessDefinitionsError(error) => {
+            shared::log::log_process_definitions_error(error);
+        }
+
         SbtBuildpackError::DetectPhaseIoError(error) => shared::log::log_please_try_again_error(
             "Unexpected I/O error",
             "An unexpected error occurred during the detect phase.",
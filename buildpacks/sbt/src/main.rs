@@ -18,9 +18,11 @@ use crate::layers::dependency_resolver_home::{
 use crate::layers::sbt_boot::handle_sbt_boot;
 use crate::layers::sbt_extras::handle_sbt_extras;
 use crate::layers::sbt_global::handle_sbt_global;
+use buildpacks_jvm_shared::processes::{additional_processes, read_process_definitions};
 use buildpacks_jvm_shared::system_properties::read_system_properties;
 use libcnb::build::{BuildContext, BuildResult, BuildResultBuilder};
 use libcnb::data::build_plan::BuildPlanBuilder;
+use libcnb::data::launch::LaunchBuilder;
 use libcnb::detect::{DetectContext, DetectResult, DetectResultBuilder};
 use libcnb::generic::{GenericMetadata, GenericPlatform};
 use libcnb::{Buildpack, Env, Error, Platform, buildpack_main};
@@ -144,7 +146,19 @@ impl Buildpack for SbtBuildpack {
                 )
             })?;
 
-            BuildResultBuilder::new().build()
+            // sbt builds have no conventional artifact to launch, processes therefore need an
+            // explicit command.
+            let processes = read_process_definitions(&context.app_dir)
+                .and_then(|process_definitions| additional_processes(&process_definitions, None))
+                .map_err(SbtBuildpackError::ProcessDefinitionsError)?;
+
+            if processes.is_empty() {
+                BuildResultBuilder::new().build()
+            } else {
+                BuildResultBuilder::new()
+                    .launch(LaunchBuilder::new().processes(processes).build())
+                    .build()
+            }
         })
     }
 
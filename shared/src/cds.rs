@@ -0,0 +1,275 @@
+use crate::framework::LaunchTarget;
+use crate::fs::list_directory_contents;
+use crate::result::none_on_not_found;
+use crate::system_properties::{ReadBooleanSettingError, read_boolean_setting};
+use libcnb::Env;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::{Duration, SystemTime};
+
+pub const JAVA_CDS_ENV_VAR_NAME: &str = "JAVA_CDS";
+const JAVA_CDS_PROPERTY_NAME: &str = "java.cds";
+
+/// The modification time the lifecycle sets for all files when exporting the image
+/// (1980-01-01T00:00:01Z).
+const NORMALIZED_MODIFICATION_TIME: Duration = Duration::from_secs(315_532_801);
+
+/// Reads whether a class data sharing archive should be created for the application from
+/// `system.properties` (`java.cds`) or the `JAVA_CDS` environment variable. The environment
+/// variable takes precedence. Archives are not created by default.
+pub fn read_cds_config(app_dir: &Path, env: &Env) -> Result<bool, ReadBooleanSettingError> {
+    read_boolean_setting(app_dir, env, JAVA_CDS_ENV_VAR_NAME, JAVA_CDS_PROPERTY_NAME)
+        .map(Option::unwrap_or_default)
+}
+
+/// The kind of archive that is created by a training run of the application.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ArchiveType {
+    /// A dynamic class data sharing archive, created with `-XX:ArchiveClassesAtExit`.
+    Cds,
+    /// An ahead-of-time cache, created with `-XX:AOTCacheOutput`. It contains the loaded and
+    /// linked classes of the application as well as method profiles.
+    AotCache,
+}
+
+impl ArchiveType {
+    /// Selects the archive type for the given Java major version. Dynamic archives are available
+    /// starting with Java 13. `-XX:AOTCacheOutput` is available starting with Java 25, Java 24
+    /// only supports creating AOT caches in two steps.
+    #[must_use]
+    pub fn for_java_version(java_major_version: u32) -> Option<Self> {
+        match java_major_version {
+            25.. => Some(ArchiveType::AotCache),
+            13.. => Some(ArchiveType::Cds),
+            _ => None,
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            ArchiveType::Cds => "app.jsa",
+            ArchiveType::AotCache => "app.aot",
+        }
+    }
+
+    fn training_option(self, archive_path: &Path) -> String {
+        match self {
+            ArchiveType::Cds => format!("-XX:ArchiveClassesAtExit={}", archive_path.display()),
+            ArchiveType::AotCache => format!("-XX:AOTCacheOutput={}", archive_path.display()),
+        }
+    }
+
+    fn runtime_option(self, archive_path: &Path) -> String {
+        match self {
+            ArchiveType::Cds => format!("-XX:SharedArchiveFile={}", archive_path.display()),
+            ArchiveType::AotCache => format!("-XX:AOTCache={}", archive_path.display()),
+        }
+    }
+}
+
+/// Reads the major version of the JDK at `java_home` from its `release` file. A missing file or
+/// `JAVA_VERSION` entry is not considered an error.
+pub fn java_major_version(java_home: &Path) -> std::io::Result<Option<u32>> {
+    none_on_not_found(std::fs::read_to_string(java_home.join("release")))
+        .map(|release| release.as_deref().and_then(parse_java_major_version))
+}
+
+fn parse_java_major_version(release: &str) -> Option<u32> {
+    let version = release
+        .lines()
+        .find_map(|line| line.strip_prefix("JAVA_VERSION="))?
+        .trim()
+        .trim_matches('"');
+
+    // Java 8 and earlier use version strings like `1.8.0_442`.
+    let version = version.strip_prefix("1.").unwrap_or(version);
+
+    version
+        .split(|char: char| !char.is_ascii_digit())
+        .next()
+        .and_then(|major_version| major_version.parse().ok())
+}
+
+/// Creates an archive of the given type in `destination_dir` by launching the application once.
+/// The Spring Boot application context is closed as soon as it has been refreshed, which ends the
+/// training run. The JVM option that makes use of the archive at launch is returned.
+pub fn create_archive(
+    env: &Env,
+    app_dir: &Path,
+    launch_target: &LaunchTarget,
+    archive_type: ArchiveType,
+    destination_dir: &Path,
+) -> Result<String, CdsError> {
+    let launch_args = match launch_target {
+        LaunchTarget::Jar(jar_path) => vec![String::from("-jar"), jar_path.display().to_string()],
+        LaunchTarget::Classpath {
+            classpath,
+            main_class,
+        } => vec![String::from("-cp"), classpath.join(":"), main_class.clone()],
+        LaunchTarget::StartScript(_) | LaunchTarget::NativeExecutable(_) => {
+            return Err(CdsError::UnsupportedLaunchTarget);
+        }
+    };
+
+    // Archives record the modification times of the JAR files on the classpath and are rejected
+    // at launch if they differ. The lifecycle normalizes the modification times of all files in
+    // the image, the training run has to see the same modification times.
+    normalize_classpath_modification_times(app_dir, launch_target).map_err(CdsError::Io)?;
+
+    let archive_path = destination_dir.join(archive_type.file_name());
+
+    let mut command = Command::new("java");
+    command
+        .current_dir(app_dir)
+        .envs(env)
+        .arg(archive_type.training_option(&archive_path))
+        .arg("-Dspring.context.exit=onRefresh")
+        .args(launch_args);
+
+    let output = command.output().map_err(CdsError::Io)?;
+    if !output.status.success() || !archive_path.is_file() {
+        return Err(CdsError::TrainingRunFailed(format!("{command:?}"), output));
+    }
+
+    Ok(archive_type.runtime_option(&archive_path))
+}
+
+fn normalize_classpath_modification_times(
+    app_dir: &Path,
+    launch_target: &LaunchTarget,
+) -> std::io::Result<()> {
+    let classpath_jars = match launch_target {
+        LaunchTarget::Jar(jar_path) => vec![app_dir.join(jar_path)],
+        LaunchTarget::Classpath { classpath, .. } => {
+            let mut jars = Vec::new();
+
+            for entry in classpath {
+                match entry.strip_suffix("/*") {
+                    Some(dir) => jars.extend(
+                        list_directory_contents(app_dir.join(dir))?
+                            .filter(|path| path.extension().is_some_and(|ext| ext == "jar")),
+                    ),
+                    None => jars.push(app_dir.join(entry)),
+                }
+            }
+
+            jars
+        }
+        LaunchTarget::StartScript(_) | LaunchTarget::NativeExecutable(_) => Vec::new(),
+    };
+
+    classpath_jars
+        .iter()
+        .filter(|path| path.is_file())
+        .try_for_each(|path| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)?
+                .set_modified(SystemTime::UNIX_EPOCH + NORMALIZED_MODIFICATION_TIME)
+        })
+}
+
+#[derive(Debug)]
+pub enum CdsError {
+    Io(std::io::Error),
+    UnsupportedLaunchTarget,
+    TrainingRunFailed(String, Output),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_java_major_versions() {
+        assert_eq!(
+            parse_java_major_version(indoc! {r#"
+                IMPLEMENTOR="Azul Systems, Inc."
+                JAVA_RUNTIME_VERSION="21.0.5+11-LTS"
+                JAVA_VERSION="21.0.5"
+                JAVA_VERSION_DATE="2024-10-15"
+            "#}),
+            Some(21)
+        );
+        assert_eq!(
+            parse_java_major_version("JAVA_VERSION=\"1.8.0_442\"\n"),
+            Some(8)
+        );
+        assert_eq!(parse_java_major_version("JAVA_VERSION=\"25\"\n"), Some(25));
+        assert_eq!(parse_java_major_version("IMPLEMENTOR=\"Azul\"\n"), None);
+    }
+
+    #[test]
+    fn classpath_modification_times_are_normalized() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let lib_dir = app_dir.path().join("lib");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+
+        for file_name in ["app.jar", "lib/dependency.jar", "lib/notes.txt"] {
+            std::fs::write(app_dir.path().join(file_name), "").unwrap();
+        }
+
+        let modification_time = |path: &str| {
+            std::fs::metadata(app_dir.path().join(path))
+                .unwrap()
+                .modified()
+                .unwrap()
+        };
+
+        let normalized_modification_time = SystemTime::UNIX_EPOCH + NORMALIZED_MODIFICATION_TIME;
+
+        normalize_classpath_modification_times(
+            app_dir.path(),
+            &LaunchTarget::Jar(PathBuf::from("app.jar")),
+        )
+        .unwrap();
+
+        assert_eq!(modification_time("app.jar"), normalized_modification_time);
+        assert_ne!(
+            modification_time("lib/dependency.jar"),
+            normalized_modification_time
+        );
+
+        normalize_classpath_modification_times(
+            app_dir.path(),
+            &LaunchTarget::Classpath {
+                classpath: vec![
+                    lib_dir.join("*").to_string_lossy().to_string(),
+                    String::from("missing.jar"),
+                ],
+                main_class: String::from("com.example.Main"),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            modification_time("lib/dependency.jar"),
+            normalized_modification_time
+        );
+        assert_ne!(
+            modification_time("lib/notes.txt"),
+            normalized_modification_time
+        );
+    }
+
+    #[test]
+    fn archive_types() {
+        assert_eq!(ArchiveType::for_java_version(8), None);
+        assert_eq!(ArchiveType::for_java_version(21), Some(ArchiveType::Cds));
+        assert_eq!(
+            ArchiveType::for_java_version(25),
+            Some(ArchiveType::AotCache)
+        );
+
+        assert_eq!(
+            ArchiveType::Cds.runtime_option(Path::new("/layers/cds/app.jsa")),
+            "-XX:SharedArchiveFile=/layers/cds/app.jsa"
+        );
+        assert_eq!(
+            ArchiveType::AotCache.training_option(Path::new("/layers/cds/app.aot")),
+            "-XX:AOTCacheOutput=/layers/cds/app.aot"
+        );
+    }
+}
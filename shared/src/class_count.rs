@@ -0,0 +1,265 @@
+use crate::framework::LaunchTarget;
+use crate::fs::list_directory_contents;
+use crate::jar::{class_count, read_manifest};
+use std::path::Path;
+
+/// Name of the launch environment variable that contains the number of classes of the
+/// application, as counted during the build. It is used to estimate the metaspace size at launch.
+pub const APPLICATION_CLASS_COUNT_ENV_VAR_NAME: &str = "JVM_APPLICATION_CLASS_COUNT";
+
+/// Counts the classes the given launch target can load: the classes of the JAR file (including
+/// nested JAR files and the JAR files of its `Class-Path` manifest attribute), of the classpath
+/// entries or of the JAR files in the `lib` directory of a start script.
+///
+/// Native executables do not load any classes, `None` is returned for these.
+pub fn launch_target_class_count(launch_target: &LaunchTarget) -> std::io::Result<Option<u64>> {
+    match launch_target {
+        LaunchTarget::Jar(jar_path) => {
+            let mut count = jar_class_count(jar_path)?;
+
+            if let (Some(jar_dir), Some(manifest_classpath)) = (
+                jar_path.parent(),
+                read_manifest(jar_path)?.remove("Class-Path"),
+            ) {
+                for entry in manifest_classpath.split_whitespace() {
+                    let path = jar_dir.join(entry);
+
+                    if path.is_file() {
+                        count += jar_class_count(&path)?;
+                    }
+                }
+            }
+
+            Ok(Some(count))
+        }
+        LaunchTarget::Classpath { classpath, .. } => {
+            let mut count = 0;
+
+            for entry in classpath {
+                count += match entry.strip_suffix("/*") {
+                    Some(dir) => jar_files_class_count(Path::new(dir))?,
+                    // Classpath directories, such as `target/classes`, are counted completely.
+                    None if Path::new(entry).is_dir() => {
+                        bounded_directory_class_count(Path::new(entry), 0, false)
+                    }
+                    None if Path::new(entry).is_file() => jar_class_count(Path::new(entry))?,
+                    None => 0,
+                };
+            }
+
+            Ok(Some(count))
+        }
+        // Start scripts of Gradle's `installDist` task add all JAR files in the `lib` directory
+        // next to the `bin` directory to the classpath.
+        LaunchTarget::StartScript(start_script) => start_script
+            .parent()
+            .and_then(Path::parent)
+            .map(|install_dir| jar_files_class_count(&install_dir.join("lib")))
+            .transpose(),
+        LaunchTarget::NativeExecutable(_) => Ok(None),
+    }
+}
+
+/// Counts the class files and the classes in JAR files in the given directory and its
+/// subdirectories. This is an estimate for applications without a known launch target.
+///
+/// Symbolic links are not followed and directories more than 24 levels deep are not counted.
+/// Hidden directories, such as dependency caches, and compiled class directories, which are
+/// usually also packaged into a JAR file, are skipped. Unreadable files and directories are not
+/// counted, as the JVM cannot load classes from them either.
+#[must_use]
+pub fn directory_class_count(dir: &Path) -> u64 {
+    bounded_directory_class_count(dir, 0, true)
+}
+
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+fn bounded_directory_class_count(dir: &Path, depth: usize, skip_build_output: bool) -> u64 {
+    let Ok(paths) = list_directory_contents(dir) else {
+        return 0;
+    };
+
+    paths
+        .map(|path| {
+            let Ok(metadata) = path.symlink_metadata() else {
+                return 0;
+            };
+
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            if metadata.is_dir() {
+                if depth < MAX_DIRECTORY_DEPTH
+                    && !(skip_build_output
+                        && (file_name.starts_with('.')
+                            || SKIPPED_DIRECTORY_NAMES.contains(&file_name.as_ref())))
+                {
+                    bounded_directory_class_count(&path, depth + 1, skip_build_output)
+                } else {
+                    0
+                }
+            } else if !metadata.is_file() {
+                0
+            } else if file_name.ends_with(".class") {
+                1
+            } else if file_name.ends_with(".jar") {
+                jar_class_count(&path).unwrap_or_default()
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+fn jar_files_class_count(dir: &Path) -> std::io::Result<u64> {
+    let mut count = 0;
+
+    // Like the JVM, classpath wildcards of missing directories are ignored.
+    if !dir.is_dir() {
+        return Ok(count);
+    }
+
+    for path in list_directory_contents(dir)? {
+        if path.extension().is_some_and(|extension| extension == "jar") && path.is_file() {
+            count += jar_class_count(&path)?;
+        }
+    }
+
+    Ok(count)
+}
+
+fn jar_class_count(jar_path: &Path) -> std::io::Result<u64> {
+    class_count(jar_path).map(|count| count as u64)
+}
+
+// Deep enough for the package hierarchies of compiled classes.
+const MAX_DIRECTORY_DEPTH: usize = 24;
+
+const SKIPPED_DIRECTORY_NAMES: [&str; 3] = ["classes", "test-classes", "node_modules"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use zip::write::SimpleFileOptions;
+
+    fn write_jar(path: &Path, files: &[(&str, &str)]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn class_count_of_launch_targets() {
+        let app_dir = tempfile::tempdir().unwrap();
+
+        write_jar(
+            &app_dir.path().join("target/app.jar"),
+            &[
+                (
+                    "META-INF/MANIFEST.MF",
+                    "Main-Class: com.example.Main\nClass-Path: lib/dependency.jar lib/missing.jar\n",
+                ),
+                ("com/example/Main.class", ""),
+            ],
+        );
+        write_jar(
+            &app_dir.path().join("target/lib/dependency.jar"),
+            &[("com/example/Dependency.class", "")],
+        );
+        write_jar(
+            &app_dir.path().join("target/lib/other.jar"),
+            &[("com/example/Other.class", "")],
+        );
+
+        assert_eq!(
+            launch_target_class_count(&LaunchTarget::Jar(app_dir.path().join("target/app.jar")))
+                .unwrap(),
+            Some(2)
+        );
+
+        assert_eq!(
+            launch_target_class_count(&LaunchTarget::Classpath {
+                classpath: vec![
+                    app_dir
+                        .path()
+                        .join("target/lib/*")
+                        .to_string_lossy()
+                        .to_string(),
+                    app_dir
+                        .path()
+                        .join("target/app.jar")
+                        .to_string_lossy()
+                        .to_string(),
+                    app_dir
+                        .path()
+                        .join("target/missing/*")
+                        .to_string_lossy()
+                        .to_string(),
+                ],
+                main_class: String::from("com.example.Main"),
+            })
+            .unwrap(),
+            Some(3)
+        );
+
+        assert_eq!(
+            launch_target_class_count(&LaunchTarget::StartScript(
+                app_dir.path().join("target/bin/app")
+            ))
+            .unwrap(),
+            Some(2)
+        );
+
+        assert_eq!(
+            launch_target_class_count(&LaunchTarget::NativeExecutable(PathBuf::from("app")))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn class_count_of_directories() {
+        let app_dir = tempfile::tempdir().unwrap();
+
+        write_jar(
+            &app_dir.path().join("target/app.jar"),
+            &[("com/example/Main.class", "")],
+        );
+        // Compiled classes are also packaged into the JAR file.
+        std::fs::create_dir_all(app_dir.path().join("target/classes/com/example")).unwrap();
+        std::fs::write(
+            app_dir.path().join("target/classes/com/example/Main.class"),
+            "",
+        )
+        .unwrap();
+        write_jar(
+            &app_dir.path().join(".m2/repository/dependency.jar"),
+            &[("com/example/Dependency.class", "")],
+        );
+        std::fs::create_dir_all(app_dir.path().join("out/com/example")).unwrap();
+        std::fs::write(app_dir.path().join("out/com/example/Util.class"), "").unwrap();
+
+        // Symbolic links are not followed, a loop must not prevent counting.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(app_dir.path(), app_dir.path().join("out/loop")).unwrap();
+
+        assert_eq!(directory_class_count(app_dir.path()), 2);
+
+        let deep_dir = (0..=MAX_DIRECTORY_DEPTH).fold(app_dir.path().join("deep"), |dir, index| {
+            dir.join(index.to_string())
+        });
+        std::fs::create_dir_all(&deep_dir).unwrap();
+        std::fs::write(deep_dir.join("Deep.class"), "").unwrap();
+
+        assert_eq!(directory_class_count(app_dir.path()), 2);
+    }
+}
@@ -0,0 +1,658 @@
+use crate::fs::list_directory_contents;
+use crate::jar::main_class;
+use crate::log::log_multiple_executable_jars_warning;
+use crate::result::none_on_not_found;
+use crate::sbom::MavenArtifact;
+use libcnb::data::launch::{Process, ProcessBuilder};
+use libcnb::data::process_type;
+use std::path::{Path, PathBuf};
+
+/// A JVM application framework that is detected from the dependencies of an application.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Framework {
+    SpringBoot,
+    WildflySwarm,
+    Quarkus,
+    Micronaut,
+    Ratpack,
+    Helidon,
+    Dropwizard,
+    Javalin,
+    Ktor,
+    Jooby,
+    Vertx,
+}
+
+/// Name of the launch environment variable that contains the [`Framework::id`] of the framework
+/// detected during the build.
+pub const FRAMEWORK_ENV_VAR_NAME: &str = "JVM_APPLICATION_FRAMEWORK";
+
+impl Framework {
+    /// A stable identifier of the framework, used to pass the detected framework to launch.
+    #[must_use]
+    pub fn id(self) -> &'static str {
+        match self {
+            Framework::SpringBoot => "spring-boot",
+            Framework::WildflySwarm => "wildfly-swarm",
+            Framework::Quarkus => "quarkus",
+            Framework::Micronaut => "micronaut",
+            Framework::Ratpack => "ratpack",
+            Framework::Helidon => "helidon",
+            Framework::Dropwizard => "dropwizard",
+            Framework::Javalin => "javalin",
+            Framework::Ktor => "ktor",
+            Framework::Jooby => "jooby",
+            Framework::Vertx => "vertx",
+        }
+    }
+}
+
+/// Detects the framework of an application from its resolved runtime dependencies.
+#[must_use]
+pub fn detect_framework(dependencies: &[MavenArtifact]) -> Option<Framework> {
+    DEPENDENCY_TO_FRAMEWORK_MAPPINGS
+        .into_iter()
+        .find_map(|(group_id, artifact_id, framework)| {
+            dependencies
+                .iter()
+                .any(|dependency| {
+                    dependency.group_id == group_id
+                        && artifact_id
+                            .is_none_or(|artifact_id| dependency.artifact_id == artifact_id)
+                })
+                .then_some(framework)
+        })
+}
+
+/// The directories in which a build tool places the artifacts of an application.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BuildOutputDirs {
+    /// The directory that contains the application JAR files, i.e. `target` or `build/libs`.
+    pub jars: PathBuf,
+    /// The directory of a Quarkus fast-jar build, i.e. `target/quarkus-app`.
+    pub quarkus_app: PathBuf,
+    /// The directory with the distributions created by Gradle's `installDist` task.
+    pub install: Option<PathBuf>,
+}
+
+/// The artifact an application is launched from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LaunchTarget {
+    Jar(PathBuf),
+    /// A start script created by Gradle's `installDist` task.
+    StartScript(PathBuf),
+    /// A main class on an explicit classpath, i.e. of an extracted Spring Boot JAR file. Classpath
+    /// entries may use wildcards.
+    Classpath {
+        classpath: Vec<String>,
+        main_class: String,
+    },
+    /// An executable compiled ahead-of-time with `native-image`. It runs without a JVM.
+    NativeExecutable(PathBuf),
+}
+
+impl LaunchTarget {
+    /// The `java` arguments that launch this target. Start scripts and native executables do not
+    /// need a `java` command and are returned as-is.
+    pub(crate) fn java_args(&self) -> String {
+        match self {
+            LaunchTarget::Jar(jar_path) => format!("-jar {}", jar_path.to_string_lossy()),
+            LaunchTarget::StartScript(path) | LaunchTarget::NativeExecutable(path) => {
+                path.to_string_lossy().to_string()
+            }
+            LaunchTarget::Classpath {
+                classpath,
+                main_class,
+            } => format!("-cp \"{}\" {main_class}", classpath.join(":")),
+        }
+    }
+}
+
+/// Finds the artifact the application should be launched from, based on its framework.
+///
+/// Without a recognized framework, the build output must contain exactly one executable JAR file.
+/// If there are multiple, a warning is shown and there is no launch target.
+/// Applications without an executable JAR file are launched with the start script of Gradle's
+/// `installDist` task, if there is one.
+pub fn find_launch_target(
+    framework: Option<Framework>,
+    build_output_dirs: &BuildOutputDirs,
+) -> Result<Option<LaunchTarget>, DefaultAppProcessError> {
+    let start_script = match &build_output_dirs.install {
+        Some(install_dir) => {
+            install_dist_start_script(install_dir).map_err(DefaultAppProcessError::Io)?
+        }
+        None => None,
+    };
+
+    // Ratpack applications are always launched with their start script if there is one.
+    if framework == Some(Framework::Ratpack)
+        && let Some(start_script) = start_script
+    {
+        return Ok(Some(LaunchTarget::StartScript(start_script)));
+    }
+
+    let jar_path = match framework {
+        Some(Framework::Quarkus) => Some(build_output_dirs.quarkus_app.join("quarkus-run.jar"))
+            .filter(|path| path.is_file()),
+        Some(_) => {
+            find_framework_jar(&build_output_dirs.jars).map_err(DefaultAppProcessError::Io)?
+        }
+        None => {
+            let mut executable_jars = find_executable_jars(&build_output_dirs.jars)
+                .map_err(DefaultAppProcessError::Io)?;
+
+            // The application has to define its processes in project.toml in this case.
+            if executable_jars.len() > 1 {
+                log_multiple_executable_jars_warning(&executable_jars);
+                return Ok(None);
+            }
+
+            executable_jars.pop()
+        }
+    };
+
+    if let Some(jar_path) = jar_path {
+        return Ok(Some(LaunchTarget::Jar(jar_path)));
+    }
+
+    // Applications without an executable JAR file can still be launched with the start script
+    // of Gradle's `installDist` task, i.e. when no fat JAR plugin is applied.
+    if let Some(start_script) = start_script {
+        return Ok(Some(LaunchTarget::StartScript(start_script)));
+    }
+
+    // Framework applications are launched from their JAR file even if it is not executable.
+    match framework {
+        Some(Framework::Quarkus) | None => Ok(None),
+        Some(_) => candidate_jars(&build_output_dirs.jars)
+            .map(|jars| jars.into_iter().next().map(LaunchTarget::Jar))
+            .map_err(DefaultAppProcessError::Io),
+    }
+}
+
+/// Creates the default `web` process that launches the given target. Framework specific
+/// configuration files, such as Dropwizard's `config.yml`, are looked up in `config_dir`.
+#[must_use]
+pub fn default_app_process(
+    framework: Option<Framework>,
+    launch_target: &LaunchTarget,
+    config_dir: &Path,
+) -> Process {
+    let command = match launch_target {
+        // Start scripts created by Gradle pass JAVA_OPTS to the JVM.
+        LaunchTarget::StartScript(start_script) => {
+            let (system_properties, args) = port_configuration(framework, config_dir);
+            let start_script = start_script.to_string_lossy();
+
+            if system_properties.is_empty() {
+                format!("{start_script}{args}")
+            } else {
+                format!("JAVA_OPTS=\"{system_properties}$JAVA_OPTS\" {start_script}{args}")
+            }
+        }
+        LaunchTarget::Jar(_) | LaunchTarget::Classpath { .. } => {
+            let (system_properties, args) = port_configuration(framework, config_dir);
+            format!(
+                "java {system_properties}$JAVA_OPTS {}{args}",
+                launch_target.java_args()
+            )
+        }
+        // Native executables accept system properties as arguments, but do not read JAVA_OPTS.
+        LaunchTarget::NativeExecutable(executable) => {
+            let (system_properties, args) = port_configuration(framework, config_dir);
+            format!("{} {system_properties}", executable.to_string_lossy())
+                .trim_end()
+                .to_string()
+                + &args
+        }
+    };
+
+    ProcessBuilder::new(process_type!("web"), ["bash", "-c", &command])
+        .default(true)
+        .build()
+}
+
+/// The system properties and arguments that configure the HTTP port of the given framework. Both
+/// are empty or padded with a space, so that they can be put around the launch arguments.
+fn port_configuration(framework: Option<Framework>, config_dir: &Path) -> (&'static str, String) {
+    match framework {
+        Some(Framework::SpringBoot | Framework::Helidon) => ("-Dserver.port=$PORT ", String::new()),
+        Some(Framework::WildflySwarm) => ("-Dswarm.http.port=$PORT ", String::new()),
+        Some(Framework::Quarkus) => ("-Dquarkus.http.port=$PORT ", String::new()),
+        Some(Framework::Micronaut) => ("-Dmicronaut.server.port=$PORT ", String::new()),
+        Some(Framework::Dropwizard) => (
+            "\"-Ddw.server.applicationConnectors[0].port=$PORT\" ",
+            ["config.yml", "config.yaml"]
+                .into_iter()
+                .map(|file_name| config_dir.join(file_name))
+                .find(|path| path.is_file())
+                .map_or_else(
+                    || String::from(" server"),
+                    |path| format!(" server {}", path.to_string_lossy()),
+                ),
+        ),
+        Some(Framework::Ktor) => ("", String::from(" -port=$PORT")),
+        Some(Framework::Jooby) => ("", String::from(" server.port=$PORT")),
+        // These have no standard way to configure the port. Applications are expected to read the
+        // PORT environment variable.
+        Some(Framework::Javalin | Framework::Vertx | Framework::Ratpack) | None => {
+            ("", String::new())
+        }
+    }
+}
+
+/// Lists the JAR files in the given directory that can be launched. Source, Javadoc and test JAR
+/// files, Spring Boot's plain JAR files and the unshaded JAR files of the Maven Shade Plugin are
+/// ignored. A missing directory is not considered an error.
+pub fn candidate_jars(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    none_on_not_found(list_directory_contents(dir)).map(|paths| {
+        let mut jars = paths
+            .into_iter()
+            .flatten()
+            .filter(|path| {
+                path.file_name()
+                    .map(|file_name| file_name.to_string_lossy().to_string())
+                    .is_some_and(|file_name| {
+                        #[allow(clippy::case_sensitive_file_extension_comparisons)]
+                        let is_jar = file_name.ends_with(".jar");
+
+                        is_jar
+                            && !IGNORED_JAR_SUFFIXES
+                                .iter()
+                                .any(|suffix| file_name.ends_with(suffix))
+                            && !file_name.starts_with("original-")
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        jars.sort();
+        jars
+    })
+}
+
+/// Finds the JAR files in the given directory that have a `Main-Class` manifest entry.
+pub fn find_executable_jars(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut executable_jars = Vec::new();
+
+    for jar_path in candidate_jars(dir)? {
+        if main_class(&jar_path)?.is_some() {
+            executable_jars.push(jar_path);
+        }
+    }
+
+    Ok(executable_jars)
+}
+
+/// Finds the executable JAR file of a framework application. Fat JAR files built by the Shadow
+/// plugin or Ktor's `buildFatJar` task (`*-all.jar`) are preferred.
+fn find_framework_jar(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let executable_jars = find_executable_jars(dir)?;
+
+    Ok(executable_jars
+        .iter()
+        .find(|jar_path| jar_path.to_string_lossy().ends_with("-all.jar"))
+        .or_else(|| executable_jars.first())
+        .cloned())
+}
+
+/// Finds the start script created by Gradle's `installDist` task in `<name>/bin/<name>`.
+fn install_dist_start_script(install_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    none_on_not_found(list_directory_contents(install_dir)).map(|paths| {
+        let mut start_scripts = paths
+            .into_iter()
+            .flatten()
+            .filter_map(|path| {
+                path.file_name()
+                    .map(|name| path.join("bin").join(name))
+                    .filter(|start_script| start_script.is_file())
+            })
+            .collect::<Vec<_>>();
+
+        start_scripts.sort();
+        start_scripts.into_iter().next()
+    })
+}
+
+#[derive(Debug)]
+pub enum DefaultAppProcessError {
+    Io(std::io::Error),
+}
+
+// Frameworks are detected in order. Quarkus applications also depend on Vert.x, which therefore
+// has to be detected last. Without an artifact id, any artifact of the group matches.
+const DEPENDENCY_TO_FRAMEWORK_MAPPINGS: [(&str, Option<&str>, Framework); 12] = [
+    (
+        "org.springframework.boot",
+        Some("spring-boot"),
+        Framework::SpringBoot,
+    ),
+    ("org.wildfly.swarm", None, Framework::WildflySwarm),
+    ("io.quarkus", Some("quarkus-core"), Framework::Quarkus),
+    ("io.micronaut", Some("micronaut-core"), Framework::Micronaut),
+    ("io.ratpack", Some("ratpack-core"), Framework::Ratpack),
+    (
+        "io.helidon.webserver",
+        Some("helidon-webserver"),
+        Framework::Helidon,
+    ),
+    (
+        "io.dropwizard",
+        Some("dropwizard-core"),
+        Framework::Dropwizard,
+    ),
+    ("io.javalin", Some("javalin"), Framework::Javalin),
+    ("io.ktor", Some("ktor-server-core"), Framework::Ktor),
+    ("io.ktor", Some("ktor-server-core-jvm"), Framework::Ktor),
+    ("io.jooby", Some("jooby"), Framework::Jooby),
+    ("io.vertx", Some("vertx-core"), Framework::Vertx),
+];
+
+const IGNORED_JAR_SUFFIXES: [&str; 4] =
+    ["-sources.jar", "-javadoc.jar", "-tests.jar", "-plain.jar"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    fn artifact(group_id: &str, artifact_id: &str) -> MavenArtifact {
+        MavenArtifact {
+            group_id: String::from(group_id),
+            artifact_id: String::from(artifact_id),
+            version: String::from("1.0.0"),
+            artifact_type: String::from("jar"),
+            classifier: None,
+        }
+    }
+
+    fn write_jar(path: &Path, manifest: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        writer
+            .start_file(
+                "META-INF/MANIFEST.MF",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(manifest.as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn build_output_dirs(app_dir: &Path) -> BuildOutputDirs {
+        BuildOutputDirs {
+            jars: app_dir.join("build/libs"),
+            quarkus_app: app_dir.join("build/quarkus-app"),
+            install: Some(app_dir.join("build/install")),
+        }
+    }
+
+    fn default_process(
+        framework: Option<Framework>,
+        build_output_dirs: &BuildOutputDirs,
+        config_dir: &Path,
+    ) -> Result<Option<Process>, DefaultAppProcessError> {
+        find_launch_target(framework, build_output_dirs).map(|launch_target| {
+            launch_target
+                .map(|launch_target| default_app_process(framework, &launch_target, config_dir))
+        })
+    }
+
+    fn bash_process(command: &str) -> Process {
+        ProcessBuilder::new(process_type!("web"), ["bash", "-c", command])
+            .default(true)
+            .build()
+    }
+
+    #[test]
+    fn detect_frameworks() {
+        for (dependencies, expected_framework) in [
+            (
+                vec![
+                    artifact("io.vertx", "vertx-core"),
+                    artifact("io.quarkus", "quarkus-core"),
+                ],
+                Some(Framework::Quarkus),
+            ),
+            (
+                vec![artifact("io.vertx", "vertx-core")],
+                Some(Framework::Vertx),
+            ),
+            (
+                vec![artifact("io.ktor", "ktor-server-core-jvm")],
+                Some(Framework::Ktor),
+            ),
+            (
+                vec![artifact("org.wildfly.swarm", "undertow")],
+                Some(Framework::WildflySwarm),
+            ),
+            (
+                vec![artifact("org.springframework.boot", "spring-boot-starter")],
+                None,
+            ),
+            (vec![], None),
+        ] {
+            assert_eq!(detect_framework(&dependencies), expected_framework);
+        }
+    }
+
+    #[test]
+    fn default_app_process_for_frameworks() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let build_output_dirs = build_output_dirs(app_dir.path());
+
+        write_jar(
+            &app_dir.path().join("build/libs/app-plain.jar"),
+            "Manifest-Version: 1.0\n",
+        );
+        write_jar(
+            &app_dir.path().join("build/libs/app.jar"),
+            "Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+        );
+        let jar_path = app_dir.path().join("build/libs/app.jar");
+        let jar_path = jar_path.to_string_lossy();
+
+        assert_eq!(
+            default_process(
+                Some(Framework::SpringBoot),
+                &build_output_dirs,
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&format!(
+                "java -Dserver.port=$PORT $JAVA_OPTS -jar {jar_path}"
+            )))
+        );
+
+        assert_eq!(
+            default_process(Some(Framework::Ktor), &build_output_dirs, app_dir.path()).unwrap(),
+            Some(bash_process(&format!(
+                "java $JAVA_OPTS -jar {jar_path} -port=$PORT"
+            )))
+        );
+
+        fs::write(app_dir.path().join("config.yml"), "server: {}\n").unwrap();
+        assert_eq!(
+            default_process(
+                Some(Framework::Dropwizard),
+                &build_output_dirs,
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&format!(
+                "java \"-Ddw.server.applicationConnectors[0].port=$PORT\" $JAVA_OPTS -jar {jar_path} server {}",
+                app_dir.path().join("config.yml").to_string_lossy()
+            )))
+        );
+
+        assert_eq!(
+            default_process(Some(Framework::Quarkus), &build_output_dirs, app_dir.path()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn default_app_process_prefers_fat_jars() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let build_output_dirs = build_output_dirs(app_dir.path());
+
+        write_jar(
+            &app_dir.path().join("build/libs/app.jar"),
+            "Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+        );
+        write_jar(
+            &app_dir.path().join("build/libs/app-all.jar"),
+            "Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+        );
+
+        assert_eq!(
+            default_process(Some(Framework::Javalin), &build_output_dirs, app_dir.path()).unwrap(),
+            Some(bash_process(&format!(
+                "java $JAVA_OPTS -jar {}",
+                app_dir
+                    .path()
+                    .join("build/libs/app-all.jar")
+                    .to_string_lossy()
+            )))
+        );
+
+        assert_eq!(
+            default_process(None, &build_output_dirs, app_dir.path()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn default_app_process_for_install_dist() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let start_script = app_dir.path().join("build/install/app/bin/app");
+        fs::create_dir_all(start_script.parent().unwrap()).unwrap();
+        fs::write(&start_script, "#!/bin/sh\n").unwrap();
+
+        assert_eq!(
+            default_process(
+                Some(Framework::Ratpack),
+                &build_output_dirs(app_dir.path()),
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&start_script.to_string_lossy()))
+        );
+    }
+
+    #[test]
+    fn default_app_process_for_install_dist_without_fat_jar() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let start_script = app_dir.path().join("build/install/app/bin/app");
+        fs::create_dir_all(start_script.parent().unwrap()).unwrap();
+        fs::write(&start_script, "#!/bin/sh\n").unwrap();
+
+        // The plain JAR file of the Application plugin has no Main-Class entry.
+        write_jar(
+            &app_dir.path().join("build/libs/app.jar"),
+            "Manifest-Version: 1.0\n",
+        );
+
+        assert_eq!(
+            default_process(
+                Some(Framework::Micronaut),
+                &build_output_dirs(app_dir.path()),
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&format!(
+                "JAVA_OPTS=\"-Dmicronaut.server.port=$PORT $JAVA_OPTS\" {}",
+                start_script.to_string_lossy()
+            )))
+        );
+
+        assert_eq!(
+            default_process(
+                Some(Framework::Ktor),
+                &build_output_dirs(app_dir.path()),
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&format!(
+                "{} -port=$PORT",
+                start_script.to_string_lossy()
+            )))
+        );
+
+        assert_eq!(
+            default_process(None, &build_output_dirs(app_dir.path()), app_dir.path()).unwrap(),
+            Some(bash_process(&start_script.to_string_lossy()))
+        );
+
+        // Executable JAR files take precedence.
+        write_jar(
+            &app_dir.path().join("build/libs/app-all.jar"),
+            "Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+        );
+
+        assert_eq!(
+            default_process(
+                Some(Framework::Ktor),
+                &build_output_dirs(app_dir.path()),
+                app_dir.path()
+            )
+            .unwrap(),
+            Some(bash_process(&format!(
+                "java $JAVA_OPTS -jar {} -port=$PORT",
+                app_dir
+                    .path()
+                    .join("build/libs/app-all.jar")
+                    .to_string_lossy()
+            )))
+        );
+    }
+
+    #[test]
+    fn default_app_process_for_classpath() {
+        assert_eq!(
+            default_app_process(
+                Some(Framework::SpringBoot),
+                &LaunchTarget::Classpath {
+                    classpath: vec![
+                        String::from("/layers/spring-boot-application/*"),
+                        String::from("/layers/spring-boot-dependencies/lib/*"),
+                    ],
+                    main_class: String::from("com.example.App"),
+                },
+                Path::new("/workspace"),
+            ),
+            bash_process(
+                "java -Dserver.port=$PORT $JAVA_OPTS -cp \"/layers/spring-boot-application/*:/layers/spring-boot-dependencies/lib/*\" com.example.App"
+            )
+        );
+    }
+
+    #[test]
+    fn default_app_process_for_native_executables() {
+        let launch_target =
+            LaunchTarget::NativeExecutable(PathBuf::from("/layers/native-image/app"));
+
+        assert_eq!(
+            default_app_process(
+                Some(Framework::SpringBoot),
+                &launch_target,
+                Path::new("/workspace")
+            ),
+            bash_process("/layers/native-image/app -Dserver.port=$PORT")
+        );
+        assert_eq!(
+            default_app_process(
+                Some(Framework::Ktor),
+                &launch_target,
+                Path::new("/workspace")
+            ),
+            bash_process("/layers/native-image/app -port=$PORT")
+        );
+        assert_eq!(
+            default_app_process(None, &launch_target, Path::new("/workspace")),
+            bash_process("/layers/native-image/app")
+        );
+    }
+}
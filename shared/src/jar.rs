@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Reads the main attributes of the `META-INF/MANIFEST.MF` file of the given JAR file.
+///
+/// A JAR file without a manifest is not considered an error. The resulting `HashMap` will be
+/// empty instead.
+pub fn read_manifest(jar_path: &Path) -> std::io::Result<HashMap<String, String>> {
+    let mut archive = zip::ZipArchive::new(File::open(jar_path)?)?;
+
+    let mut manifest = String::new();
+    match archive.by_name(MANIFEST_PATH) {
+        Ok(mut file) => {
+            file.read_to_string(&mut manifest)?;
+        }
+        Err(zip::result::ZipError::FileNotFound) => {}
+        Err(error) => return Err(error.into()),
+    }
+
+    Ok(parse_manifest_main_attributes(&manifest))
+}
+
+/// Returns the `Main-Class` of the given JAR file, if it is an executable JAR file.
+pub fn main_class(jar_path: &Path) -> std::io::Result<Option<String>> {
+    read_manifest(jar_path).map(|mut attributes| {
+        attributes
+            .remove("Main-Class")
+            .filter(|main_class| !main_class.is_empty())
+    })
+}
+
+/// Counts the class files in the given JAR file, including the class files of nested JAR files,
+/// such as the dependencies in `BOOT-INF/lib` of a Spring Boot JAR file. Only the central
+/// directories are read. Nested JAR files are read in place, which requires them to be stored
+/// uncompressed, as Spring Boot does. Compressed nested JAR files are not counted.
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+pub fn class_count(jar_path: &Path) -> std::io::Result<usize> {
+    let mut archive = zip::ZipArchive::new(File::open(jar_path)?)?;
+
+    let nested_jar_indices = archive
+        .file_names()
+        .filter(|file_name| file_name.ends_with(".jar"))
+        .filter_map(|file_name| archive.index_for_name(file_name))
+        .collect::<Vec<_>>();
+
+    let mut count = archive_class_count(&archive);
+
+    for index in nested_jar_indices {
+        let nested_jar = archive.by_index_raw(index)?;
+
+        if nested_jar.compression() == zip::CompressionMethod::Stored {
+            let section = FileSection::new(
+                File::open(jar_path)?,
+                nested_jar.data_start(),
+                nested_jar.compressed_size(),
+            );
+
+            count += archive_class_count(&zip::ZipArchive::new(section)?);
+        }
+    }
+
+    Ok(count)
+}
+
+/// A section of a file, i.e. the data of an uncompressed entry in a ZIP file.
+struct FileSection {
+    file: File,
+    start: u64,
+    length: u64,
+    position: u64,
+}
+
+impl FileSection {
+    fn new(file: File, start: u64, length: u64) -> Self {
+        FileSection {
+            file,
+            start,
+            length,
+            position: 0,
+        }
+    }
+}
+
+impl Read for FileSection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.file
+            .seek(SeekFrom::Start(self.start + self.position))?;
+        let read = (&mut self.file)
+            .take(self.length - self.position)
+            .read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for FileSection {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?
+        .min(self.length);
+
+        Ok(self.position)
+    }
+}
+
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+fn archive_class_count<R: Read + Seek>(archive: &zip::ZipArchive<R>) -> usize {
+    archive
+        .file_names()
+        .filter(|file_name| file_name.ends_with(".class"))
+        .count()
+}
+
+/// Parses the main section of a JAR manifest. Lines longer than 72 bytes are continued on the
+/// next line, prefixed with a single space. The main section ends at the first empty line.
+fn parse_manifest_main_attributes(manifest: &str) -> HashMap<String, String> {
+    let mut lines = Vec::<String>::new();
+
+    for line in manifest.lines() {
+        if line.is_empty() {
+            break;
+        } else if let Some(continuation) = line.strip_prefix(' ') {
+            if let Some(last_line) = lines.last_mut() {
+                last_line.push_str(continuation);
+            }
+        } else {
+            lines.push(String::from(line));
+        }
+    }
+
+    lines
+        .iter()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (String::from(key.trim()), String::from(value.trim())))
+        .collect()
+}
+
+const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn write_jar(path: &Path, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn parse_manifest_with_continuation_lines() {
+        let attributes = parse_manifest_main_attributes(
+            "Manifest-Version: 1.0\r\nMain-Class: com.example.very.long.package.name.that.does.not.fit.on.one.l\r\n ine.Main\r\nCreated-By: Maven JAR Plugin 3.4.1\r\n\r\nName: com/example/\r\nSealed: true\r\n",
+        );
+
+        assert_eq!(
+            attributes.get("Main-Class").map(String::as_str),
+            Some("com.example.very.long.package.name.that.does.not.fit.on.one.line.Main")
+        );
+        assert_eq!(
+            attributes.get("Created-By").map(String::as_str),
+            Some("Maven JAR Plugin 3.4.1")
+        );
+        assert_eq!(attributes.get("Sealed"), None);
+    }
+
+    #[test]
+    fn main_class_of_jar_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let executable_jar_path = temp_dir.path().join("app.jar");
+        write_jar(
+            &executable_jar_path,
+            &[(
+                MANIFEST_PATH,
+                "Manifest-Version: 1.0\nMain-Class: com.example.Main\n",
+            )],
+        );
+
+        let library_jar_path = temp_dir.path().join("lib.jar");
+        write_jar(
+            &library_jar_path,
+            &[(MANIFEST_PATH, "Manifest-Version: 1.0\n")],
+        );
+
+        let jar_without_manifest_path = temp_dir.path().join("plain.jar");
+        write_jar(&jar_without_manifest_path, &[("com/example/Lib.class", "")]);
+
+        assert_eq!(
+            main_class(&executable_jar_path).unwrap().as_deref(),
+            Some("com.example.Main")
+        );
+        assert_eq!(main_class(&library_jar_path).unwrap(), None);
+        assert_eq!(main_class(&jar_without_manifest_path).unwrap(), None);
+    }
+
+    #[test]
+    fn class_count_of_jar_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let jar_path = temp_dir.path().join("lib.jar");
+
+        write_jar(
+            &jar_path,
+            &[
+                (MANIFEST_PATH, "Manifest-Version: 1.0\n"),
+                ("com/example/Lib.class", ""),
+                ("com/example/Lib$Inner.class", ""),
+                ("com/example/lib.properties", ""),
+            ],
+        );
+
+        assert_eq!(class_count(&jar_path).unwrap(), 2);
+    }
+
+    #[test]
+    fn class_count_of_jar_file_with_nested_jar_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let nested_jar_path = temp_dir.path().join("lib.jar");
+        write_jar(
+            &nested_jar_path,
+            &[
+                ("com/example/Lib.class", ""),
+                ("com/example/Util.class", ""),
+            ],
+        );
+        let nested_jar = std::fs::read(&nested_jar_path).unwrap();
+
+        let jar_path = temp_dir.path().join("app.jar");
+        let mut writer = zip::ZipWriter::new(File::create(&jar_path).unwrap());
+        writer
+            .start_file(
+                "BOOT-INF/classes/com/example/Main.class",
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer
+            .start_file(
+                "BOOT-INF/lib/lib.jar",
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored),
+            )
+            .unwrap();
+        writer.write_all(&nested_jar).unwrap();
+        // Compressed nested JAR files cannot be read in place and are not counted.
+        writer
+            .start_file("BOOT-INF/lib/compressed.jar", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&nested_jar).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(class_count(&jar_path).unwrap(), 3);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::cds::{ArchiveType, create_archive, java_major_version};
+use crate::framework::LaunchTarget;
+use crate::log::{log_cds_unsupported_warning, log_cds_warning};
+use crate::output;
+use crate::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use libcnb::Buildpack;
+use libcnb::Env;
+use libcnb::build::BuildContext;
+use libcnb::data::layer_name;
+use libcnb::layer::UncachedLayerDefinition;
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+use std::path::Path;
+
+/// Creates a class data sharing archive (or AOT cache) with a training run of the application and
+/// adds the JVM option that uses it to `JAVA_OPTS` at launch. A failed training run is not
+/// considered an error, the application is launched without an archive in that case.
+pub fn handle_cds_layer<B>(
+    context: &BuildContext<B>,
+    env: &Env,
+    launch_target: &LaunchTarget,
+) -> libcnb::Result<(), B::Error>
+where
+    B: Buildpack,
+    B::Error: From<ReadJavaVersionError>,
+{
+    output::print_section("Creating class data sharing archive");
+
+    let archive_type = env
+        .get("JAVA_HOME")
+        .map(|java_home| java_major_version(Path::new(&java_home)))
+        .transpose()
+        .map_err(|error| libcnb::Error::BuildpackError(ReadJavaVersionError(error).into()))?
+        .flatten()
+        .and_then(ArchiveType::for_java_version);
+
+    let Some(archive_type) = archive_type else {
+        log_cds_unsupported_warning();
+        return Ok(());
+    };
+
+    let layer_ref = context.uncached_layer(
+        layer_name!("cds"),
+        UncachedLayerDefinition {
+            build: false,
+            launch: true,
+        },
+    )?;
+
+    let result = output::track_subsection_timing(|| {
+        output::print_subsection(BuildpackOutputText::new(vec![
+            BuildpackOutputTextSection::regular("Running the application with "),
+            BuildpackOutputTextSection::value("-Dspring.context.exit=onRefresh"),
+        ]));
+
+        Ok::<_, libcnb::Error<B::Error>>(create_archive(
+            env,
+            &context.app_dir,
+            launch_target,
+            archive_type,
+            &layer_ref.path(),
+        ))
+    })?;
+
+    match result {
+        Ok(runtime_option) => layer_ref.write_env(
+            LayerEnv::new()
+                .chainable_insert(
+                    Scope::Launch,
+                    ModificationBehavior::Delimiter,
+                    "JAVA_OPTS",
+                    " ",
+                )
+                .chainable_insert(
+                    Scope::Launch,
+                    ModificationBehavior::Prepend,
+                    "JAVA_OPTS",
+                    runtime_option,
+                ),
+        ),
+        Err(error) => {
+            log_cds_warning(error);
+            Ok(())
+        }
+    }
+}
+
+/// The major version of the OpenJDK in `JAVA_HOME` could not be read.
+#[derive(Debug)]
+pub struct ReadJavaVersionError(pub std::io::Error);
@@ -0,0 +1,46 @@
+use crate::class_count::{APPLICATION_CLASS_COUNT_ENV_VAR_NAME, launch_target_class_count};
+use crate::framework::LaunchTarget;
+use crate::output;
+use crate::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use libcnb::Buildpack;
+use libcnb::build::BuildContext;
+use libcnb::data::layer_name;
+use libcnb::layer::UncachedLayerDefinition;
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+
+/// Counts the classes of the launch target once during the build and provides the count at launch,
+/// where it is used to estimate the metaspace size. If the classes cannot be counted, the count is
+/// estimated at launch instead.
+pub fn handle_class_count_layer<B>(
+    context: &BuildContext<B>,
+    launch_target: &LaunchTarget,
+) -> libcnb::Result<(), B::Error>
+where
+    B: Buildpack,
+{
+    let Ok(Some(class_count)) = launch_target_class_count(launch_target) else {
+        return Ok(());
+    };
+
+    output::print_section("Counting application classes");
+    output::print_subsection(BuildpackOutputText::new(vec![
+        BuildpackOutputTextSection::regular("Found "),
+        BuildpackOutputTextSection::value(class_count.to_string()),
+        BuildpackOutputTextSection::regular(" classes"),
+    ]));
+
+    let layer_ref = context.uncached_layer(
+        layer_name!("class-count"),
+        UncachedLayerDefinition {
+            build: false,
+            launch: true,
+        },
+    )?;
+
+    layer_ref.write_env(LayerEnv::new().chainable_insert(
+        Scope::Launch,
+        ModificationBehavior::Override,
+        APPLICATION_CLASS_COUNT_ENV_VAR_NAME,
+        class_count.to_string(),
+    ))
+}
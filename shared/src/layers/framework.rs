@@ -0,0 +1,31 @@
+use crate::framework::{FRAMEWORK_ENV_VAR_NAME, Framework};
+use libcnb::Buildpack;
+use libcnb::build::BuildContext;
+use libcnb::data::layer_name;
+use libcnb::layer::UncachedLayerDefinition;
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+
+/// Provides the framework detected during the build at launch, so that launch time configuration,
+/// such as the framework specific datasource environment variables, only applies to it.
+pub fn handle_framework_layer<B>(
+    context: &BuildContext<B>,
+    framework: Framework,
+) -> libcnb::Result<(), B::Error>
+where
+    B: Buildpack,
+{
+    let layer_ref = context.uncached_layer(
+        layer_name!("framework"),
+        UncachedLayerDefinition {
+            build: false,
+            launch: true,
+        },
+    )?;
+
+    layer_ref.write_env(LayerEnv::new().chainable_insert(
+        Scope::Launch,
+        ModificationBehavior::Override,
+        FRAMEWORK_ENV_VAR_NAME,
+        framework.id(),
+    ))
+}
@@ -0,0 +1,64 @@
+use crate::output;
+use crate::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use crate::runtime_image::{
+    RuntimeImageConfig, RuntimeImageError, create_jlink_image, find_application_jars,
+};
+use libcnb::Buildpack;
+use libcnb::build::BuildContext;
+use libcnb::data::layer_name;
+use libcnb::layer::UncachedLayerDefinition;
+use libcnb::layer_env::{LayerEnv, ModificationBehavior, Scope};
+use std::path::Path;
+
+/// Creates a launch-only Java runtime with jlink that only contains the modules required by the
+/// build output in `output_dir`.
+pub fn handle_jlink_jre_layer<B>(
+    context: &BuildContext<B>,
+    java_home: Option<&Path>,
+    output_dir: &Path,
+    runtime_image_config: &RuntimeImageConfig,
+) -> libcnb::Result<(), B::Error>
+where
+    B: Buildpack,
+    B::Error: From<RuntimeImageError>,
+{
+    output::print_section("Creating jlink runtime for launch");
+
+    let java_home = java_home.ok_or_else(|| {
+        libcnb::Error::BuildpackError(RuntimeImageError::JlinkNotAvailable.into())
+    })?;
+
+    let application_jars = find_application_jars(output_dir)
+        .map_err(|error| libcnb::Error::BuildpackError(RuntimeImageError::Io(error).into()))?;
+
+    let layer_ref = context.uncached_layer(
+        layer_name!("jre"),
+        UncachedLayerDefinition {
+            build: false,
+            launch: true,
+        },
+    )?;
+
+    let modules = output::track_subsection_timing(|| {
+        output::print_subsection("Analyzing application JAR files with jdeps");
+        create_jlink_image(
+            java_home,
+            &application_jars,
+            &runtime_image_config.additional_modules,
+            &layer_ref.path(),
+        )
+        .map_err(|error| libcnb::Error::BuildpackError(error.into()))
+    })?;
+
+    output::print_subsection(BuildpackOutputText::new(vec![
+        BuildpackOutputTextSection::regular("Linked modules: "),
+        BuildpackOutputTextSection::value(modules.into_iter().collect::<Vec<_>>().join(", ")),
+    ]));
+
+    layer_ref.write_env(LayerEnv::new().chainable_insert(
+        Scope::Launch,
+        ModificationBehavior::Override,
+        "JAVA_HOME",
+        layer_ref.path(),
+    ))
+}
@@ -0,0 +1,9 @@
+//! Layer handlers that are shared between the Maven and Gradle buildpacks. They are generic over
+//! the buildpack and convert their errors into the buildpack's error type with `From`.
+
+pub mod cds;
+pub mod class_count;
+pub mod framework;
+pub mod jre;
+pub mod native_image;
+pub mod spring_boot;
@@ -0,0 +1,43 @@
+use crate::framework::LaunchTarget;
+use crate::native_image::{NativeImageError, find_native_executable, install_native_executable};
+use crate::output;
+use crate::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use libcnb::Buildpack;
+use libcnb::build::BuildContext;
+use libcnb::data::layer_name;
+use libcnb::layer::UncachedLayerDefinition;
+use std::path::Path;
+
+/// Installs the native executable that was compiled into `output_dir` into a launch-only layer.
+/// The executable replaces the JVM at launch.
+pub fn handle_native_image_layer<B>(
+    context: &BuildContext<B>,
+    output_dir: &Path,
+) -> libcnb::Result<LaunchTarget, B::Error>
+where
+    B: Buildpack,
+    B::Error: From<NativeImageError>,
+{
+    output::print_section("Installing native executable");
+
+    let executable = find_native_executable(output_dir)
+        .map_err(|error| libcnb::Error::BuildpackError(error.into()))?;
+
+    let layer_ref = context.uncached_layer(
+        layer_name!("native-image"),
+        UncachedLayerDefinition {
+            build: false,
+            launch: true,
+        },
+    )?;
+
+    let installed_executable = install_native_executable(&executable, &layer_ref.path())
+        .map_err(|error| libcnb::Error::BuildpackError(error.into()))?;
+
+    output::print_subsection(BuildpackOutputText::new(vec![
+        BuildpackOutputTextSection::regular("Installed "),
+        BuildpackOutputTextSection::value(executable.to_string_lossy()),
+    ]));
+
+    Ok(LaunchTarget::NativeExecutable(installed_executable))
+}
@@ -0,0 +1,111 @@
+use crate::framework::LaunchTarget;
+use crate::output;
+use crate::output::{BuildpackOutputText, BuildpackOutputTextSection};
+use crate::spring_boot::{SpringBootLayersError, extract_layers, install_layer, layers_classpath};
+use libcnb::Buildpack;
+use libcnb::Env;
+use libcnb::build::BuildContext;
+use libcnb::data::layer::LayerName;
+use libcnb::layer::{
+    CachedLayerDefinition, InvalidMetadataAction, LayerState, RestoredLayerAction,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Extracts the layers of a Spring Boot JAR file into separate launch layers. Each layer is
+/// cached by the digest of its contents, so that unchanged layers are reused. The JAR file is
+/// removed from the application directory afterwards.
+pub fn handle_spring_boot_layers<B>(
+    context: &BuildContext<B>,
+    env: &Env,
+    jar_path: &Path,
+    start_class: &str,
+) -> libcnb::Result<LaunchTarget, B::Error>
+where
+    B: Buildpack,
+    B::Error: From<SpringBootLayersError>,
+{
+    output::print_section("Extracting Spring Boot layers");
+
+    let extract_dir = tempfile::tempdir()
+        .map_err(|error| libcnb::Error::BuildpackError(SpringBootLayersError::Io(error).into()))?;
+
+    let extracted_layers = output::track_subsection_timing(|| {
+        output::print_subsection(BuildpackOutputText::new(vec![
+            BuildpackOutputTextSection::regular("Running "),
+            BuildpackOutputTextSection::command(format!(
+                "java -Djarmode=tools -jar {} extract --layers",
+                jar_path.to_string_lossy()
+            )),
+        ]));
+
+        extract_layers(env, jar_path, extract_dir.path())
+            .map_err(|error| libcnb::Error::BuildpackError(error.into()))
+    })?;
+
+    let mut layer_dirs = Vec::new();
+    for extracted_layer in extracted_layers {
+        let layer_name = format!("spring-boot-{}", extracted_layer.name)
+            .parse::<LayerName>()
+            .map_err(|_| {
+                libcnb::Error::BuildpackError(
+                    SpringBootLayersError::InvalidLayerName(extracted_layer.name.clone()).into(),
+                )
+            })?;
+
+        let layer_ref = context.cached_layer(
+            layer_name,
+            CachedLayerDefinition {
+                build: false,
+                launch: true,
+                invalid_metadata_action: &|_| InvalidMetadataAction::DeleteLayer,
+                restored_layer_action: &|metadata: &SpringBootLayerMetadata, _| {
+                    if metadata.digest == extracted_layer.digest {
+                        RestoredLayerAction::KeepLayer
+                    } else {
+                        RestoredLayerAction::DeleteLayer
+                    }
+                },
+            },
+        )?;
+
+        match layer_ref.state {
+            LayerState::Restored { .. } => {
+                output::print_subsection(BuildpackOutputText::new(vec![
+                    BuildpackOutputTextSection::regular("Reusing unchanged layer "),
+                    BuildpackOutputTextSection::value(&extracted_layer.name),
+                ]));
+            }
+            LayerState::Empty { .. } => {
+                install_layer(&extracted_layer, &layer_ref.path())
+                    .map_err(|error| libcnb::Error::BuildpackError(error.into()))?;
+
+                layer_ref.write_metadata(SpringBootLayerMetadata {
+                    digest: extracted_layer.digest.clone(),
+                })?;
+
+                output::print_subsection(BuildpackOutputText::new(vec![
+                    BuildpackOutputTextSection::regular("Created layer "),
+                    BuildpackOutputTextSection::value(&extracted_layer.name),
+                ]));
+            }
+        }
+
+        layer_dirs.push((extracted_layer.name, layer_ref.path()));
+    }
+
+    // The extracted layers replace the JAR file. Keeping it would add all dependencies to the
+    // application directory again.
+    std::fs::remove_file(jar_path)
+        .map_err(|error| libcnb::Error::BuildpackError(SpringBootLayersError::Io(error).into()))?;
+
+    Ok(LaunchTarget::Classpath {
+        classpath: layers_classpath(&layer_dirs),
+        main_class: String::from(start_class),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SpringBootLayerMetadata {
+    digest: String,
+}
@@ -1,5 +1,15 @@
+pub mod cds;
+pub mod class_count;
+pub mod framework;
 pub mod fs;
+pub mod jar;
+pub mod layers;
 pub mod log;
+pub mod native_image;
 pub mod output;
+pub mod processes;
 pub mod result;
+pub mod runtime_image;
+pub mod sbom;
+pub mod spring_boot;
 pub mod system_properties;
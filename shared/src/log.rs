@@ -1,6 +1,15 @@
+use crate::cds::CdsError;
+use crate::native_image::NativeImageError;
 use crate::output;
+use crate::processes::ProcessDefinitionsError;
+use crate::runtime_image::{
+    JAVA_RUNTIME_IMAGE_ENV_VAR_NAME, RuntimeImageConfigError, RuntimeImageError,
+};
+use crate::spring_boot::SpringBootLayersError;
+use crate::system_properties::{ReadBooleanSettingError, ReadSystemPropertiesError};
 use indoc::formatdoc;
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 
 pub fn log_please_try_again<H: AsRef<str>, M: AsRef<str>>(header: H, message: M) {
@@ -58,3 +67,266 @@ pub fn log_build_tool_io_error(build_tool_name: &str, error: std::io::Error) {
         error,
     );
 }
+
+pub fn log_multiple_executable_jars_warning(jar_paths: &[PathBuf]) {
+    output::print_warning(
+        "Multiple executable JAR files",
+        formatdoc! {"
+            Your application's build produced more than one executable JAR file (with a Main-Class
+            manifest entry). We cannot determine which one should be used for the default process:
+
+            {jar_paths}
+
+            No default web process will be created. Please change your build to only produce one
+            executable JAR file, or define the processes of your application in the
+            [com.heroku.buildpacks.jvm.processes] table of your application's project.toml file:
+
+            [com.heroku.buildpacks.jvm.processes.web]
+            command = \"java $JAVA_OPTS -jar target/app.jar\"
+        ", jar_paths = jar_paths.iter().map(|jar_path| format!("- {}", jar_path.to_string_lossy())).collect::<Vec<_>>().join("\n") },
+    );
+}
+
+pub fn log_process_definitions_error(error: ProcessDefinitionsError) {
+    match error {
+        ProcessDefinitionsError::Io(error) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not read your application's project.toml file due to an unexpected I/O error.",
+            error,
+        ),
+        ProcessDefinitionsError::Parse(error) => output::print_error(
+            "Invalid process definitions",
+            formatdoc! {"
+                Could not parse the processes defined in the [com.heroku.buildpacks.jvm.processes]
+                table of your application's project.toml file. Each process supports the keys
+                command, args and java-opts.
+
+                Details: {error}
+            "},
+        ),
+        ProcessDefinitionsError::InvalidProcessType(error) => output::print_error(
+            "Invalid process type",
+            formatdoc! {"
+                A process type defined in the [com.heroku.buildpacks.jvm.processes] table of your
+                application's project.toml file is invalid. Process types may only contain letters,
+                numbers, '.', '_' and '-'.
+
+                Details: {error}
+            "},
+        ),
+        ProcessDefinitionsError::MissingLaunchTarget(process_type) => output::print_error(
+            "No application to launch",
+            formatdoc! {"
+                The process '{process_type}' defined in your application's project.toml file has no
+                command and your application's build did not produce a JAR file or start script
+                that could be launched instead.
+
+                Please add a command to the process definition or change your build to produce an
+                executable JAR file.
+            "},
+        ),
+    }
+}
+
+pub fn log_runtime_image_config_error(error: RuntimeImageConfigError) {
+    match error {
+        RuntimeImageConfigError::ReadSystemProperties(ReadSystemPropertiesError::ParseError(
+            error,
+        )) => output::print_error(
+            "Invalid system.properties file",
+            formatdoc! {"
+                Could not parse your application's system.properties file. Please ensure that your
+                system.properties file is a valid Java properties file and try again.
+
+                Details: {error}
+            "},
+        ),
+        RuntimeImageConfigError::ReadSystemProperties(ReadSystemPropertiesError::IoError(
+            error,
+        )) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not read your application's system.properties file due to an unexpected I/O error.",
+            error,
+        ),
+        RuntimeImageConfigError::UnknownImageType(value) => output::print_error(
+            "Unknown Java runtime image type",
+            formatdoc! {"
+                The Java runtime image type '{value}' configured with java.runtime.image in your
+                system.properties file or the {JAVA_RUNTIME_IMAGE_ENV_VAR_NAME} environment variable is not supported.
+                Supported types are: jdk, jre, jlink and native.
+            "},
+        ),
+    }
+}
+
+pub fn log_runtime_image_error(error: RuntimeImageError) {
+    match error {
+        RuntimeImageError::JlinkNotAvailable => output::print_error(
+            "jlink not available",
+            formatdoc! {"
+                A jlink runtime image was requested, but the installed OpenJDK does not contain
+                jlink. jlink is available starting with OpenJDK 9. Please use a newer OpenJDK
+                version or set java.runtime.image to jre in your system.properties file.
+            "},
+        ),
+        RuntimeImageError::CommandFailed(command, output) => output::print_error(
+            "Creating the Java runtime image failed",
+            formatdoc! {"
+                The command used to create the Java runtime image for launch failed. If jdeps
+                or jlink report missing modules, you can add modules with java.runtime.jlink.modules
+                in your system.properties file.
+
+                Command: {command}
+                Output:
+                {stdout}{stderr}
+            ", stdout = String::from_utf8_lossy(&output.stdout), stderr = String::from_utf8_lossy(&output.stderr)},
+        ),
+        RuntimeImageError::Io(error) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not create the Java runtime image for launch due to an unexpected I/O error.",
+            error,
+        ),
+    }
+}
+
+pub fn log_native_image_error(error: NativeImageError) {
+    match error {
+        NativeImageError::NativeImageNotAvailable => output::print_error(
+            "native-image not available",
+            formatdoc! {"
+                A native executable was requested, but the installed OpenJDK does not contain
+                native-image. Please install a GraalVM tarball by setting java.runtime.url and
+                java.runtime.sha256 in your system.properties file.
+            "},
+        ),
+        NativeImageError::ExecutableNotFound(dir) => output::print_error(
+            "Native executable not found",
+            formatdoc! {"
+                Your application's build did not produce a native executable in {dir}.
+                Please ensure that the GraalVM Native Build Tools plugin is configured for your
+                build and try again.
+            ", dir = dir.to_string_lossy() },
+        ),
+        NativeImageError::MultipleExecutables(paths) => output::print_error(
+            "Multiple native executables",
+            formatdoc! {"
+                Your application's build produced more than one native executable. We cannot
+                determine which one should be used for the default process:
+
+                {paths}
+
+                Please change your build to only produce one native executable.
+            ", paths = paths.iter().map(|path| format!("- {}", path.to_string_lossy())).collect::<Vec<_>>().join("\n") },
+        ),
+        NativeImageError::Io(error) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not install the native executable for launch due to an unexpected I/O error.",
+            error,
+        ),
+    }
+}
+
+pub fn log_read_boolean_setting_error(error: ReadBooleanSettingError) {
+    match error {
+        ReadBooleanSettingError::ReadSystemProperties(ReadSystemPropertiesError::ParseError(
+            error,
+        )) => output::print_error(
+            "Invalid system.properties file",
+            formatdoc! {"
+                Could not parse your application's system.properties file. Please ensure that your
+                system.properties file is a valid Java properties file and try again.
+
+                Details: {error}
+            "},
+        ),
+        ReadBooleanSettingError::ReadSystemProperties(ReadSystemPropertiesError::IoError(
+            error,
+        )) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not read your application's system.properties file due to an unexpected I/O error.",
+            error,
+        ),
+        ReadBooleanSettingError::InvalidValue { name, value } => output::print_error(
+            "Invalid configuration value",
+            formatdoc! {"
+                The value '{value}' configured for {name} is invalid. Supported values are: true
+                and false.
+            "},
+        ),
+    }
+}
+
+pub fn log_spring_boot_layers_error(error: SpringBootLayersError) {
+    match error {
+        SpringBootLayersError::CommandFailed(command, output) => output::print_error(
+            "Extracting Spring Boot layers failed",
+            formatdoc! {"
+                The command used to extract the layers of your application's Spring Boot JAR file
+                failed. Extracting layers requires Spring Boot 3.3 or later.
+
+                Command: {command}
+                Output:
+                {stdout}{stderr}
+            ", stdout = String::from_utf8_lossy(&output.stdout), stderr = String::from_utf8_lossy(&output.stderr)},
+        ),
+        SpringBootLayersError::InvalidLayerName(name) => output::print_error(
+            "Invalid Spring Boot layer name",
+            formatdoc! {"
+                The layer '{name}' of your application's Spring Boot JAR file cannot be used as the
+                name of an image layer. Please only use letters, numbers, '.', '_' and '-' in the
+                names of the layers configured for the Spring Boot build plugin.
+            "},
+        ),
+        SpringBootLayersError::Io(error) => log_please_try_again_error(
+            "Unexpected IO error",
+            "Could not extract the layers of your application's Spring Boot JAR file due to an unexpected I/O error.",
+            error,
+        ),
+    }
+}
+
+pub fn log_spring_boot_jar_not_layered_warning(jar_path: &Path) {
+    output::print_warning(
+        "Spring Boot JAR file has no layers",
+        formatdoc! {"
+            Extracting Spring Boot layers was requested, but {jar_path} was built without a layers
+            index or Start-Class. The application will be launched from the JAR file instead.
+        ", jar_path = jar_path.to_string_lossy() },
+    );
+}
+
+pub fn log_cds_unsupported_warning() {
+    output::print_warning(
+        "Class data sharing archive not created",
+        formatdoc! {"
+            Creating a class data sharing archive was requested, but archives can only be created
+            for Spring Boot applications that are launched from a JAR file with the full JDK
+            (java.runtime.image=jdk) in Java 13 or later.
+        "},
+    );
+}
+
+pub fn log_cds_warning(error: CdsError) {
+    match error {
+        CdsError::TrainingRunFailed(command, output) => output::print_warning(
+            "Class data sharing archive not created",
+            formatdoc! {"
+                The training run of your application failed, the application will be launched without
+                a class data sharing archive. The training run closes the Spring application context
+                as soon as it has been refreshed. Applications that connect to other services on
+                startup, i.e. databases, might not be able to start during the build.
+
+                Command: {command}
+                Output:
+                {stdout}{stderr}
+            ", stdout = String::from_utf8_lossy(&output.stdout), stderr = String::from_utf8_lossy(&output.stderr)},
+        ),
+        CdsError::UnsupportedLaunchTarget => log_cds_unsupported_warning(),
+        CdsError::Io(error) => output::print_warning(
+            "Class data sharing archive not created",
+            format!(
+                "An unexpected I/O error occurred during the training run of your application: {error}"
+            ),
+        ),
+    }
+}
@@ -0,0 +1,154 @@
+use crate::fs::list_directory_contents;
+use crate::result::none_on_not_found;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Checks that the JDK at `java_home` can compile native executables, i.e. that it contains the
+/// `native-image` tool.
+pub fn ensure_native_image_available(java_home: Option<&Path>) -> Result<(), NativeImageError> {
+    if java_home.is_some_and(|java_home| java_home.join("bin").join("native-image").is_file()) {
+        Ok(())
+    } else {
+        Err(NativeImageError::NativeImageNotAvailable)
+    }
+}
+
+/// Finds the native executable that was compiled into the given directory. The directory must
+/// contain exactly one executable ELF file that is not a shared library.
+pub fn find_native_executable(dir: &Path) -> Result<PathBuf, NativeImageError> {
+    let mut executables = Vec::new();
+
+    for path in none_on_not_found(list_directory_contents(dir))
+        .map_err(NativeImageError::Io)?
+        .into_iter()
+        .flatten()
+    {
+        if !is_shared_library(&path)
+            && is_executable_elf_file(&path).map_err(NativeImageError::Io)?
+        {
+            executables.push(path);
+        }
+    }
+
+    executables.sort();
+
+    match executables.len() {
+        0 => Err(NativeImageError::ExecutableNotFound(dir.to_path_buf())),
+        1 => Ok(executables.remove(0)),
+        _ => Err(NativeImageError::MultipleExecutables(executables)),
+    }
+}
+
+/// Copies the native executable and the shared libraries next to it, such as `libawt.so`, into
+/// the (empty) `destination` directory. The path of the copied executable is returned.
+pub fn install_native_executable(
+    executable: &Path,
+    destination: &Path,
+) -> Result<PathBuf, NativeImageError> {
+    let mut paths = vec![executable.to_path_buf()];
+
+    if let Some(dir) = executable.parent() {
+        paths.extend(
+            list_directory_contents(dir)
+                .map_err(NativeImageError::Io)?
+                .filter(|path| path.is_file() && is_shared_library(path)),
+        );
+    }
+
+    for path in &paths {
+        if let Some(file_name) = path.file_name() {
+            std::fs::copy(path, destination.join(file_name)).map_err(NativeImageError::Io)?;
+        }
+    }
+
+    Ok(destination.join(executable.file_name().unwrap_or_default()))
+}
+
+#[derive(Debug)]
+pub enum NativeImageError {
+    Io(std::io::Error),
+    NativeImageNotAvailable,
+    ExecutableNotFound(PathBuf),
+    MultipleExecutables(Vec<PathBuf>),
+}
+
+fn is_shared_library(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "so")
+}
+
+fn is_executable_elf_file(path: &Path) -> std::io::Result<bool> {
+    let metadata = path.metadata()?;
+    if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+        return Ok(false);
+    }
+
+    let mut magic = [0; 4];
+    let mut file = std::fs::File::open(path)?;
+
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == ELF_MAGIC),
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::set_executable;
+    use std::fs;
+
+    #[test]
+    fn find_native_executable_ignores_other_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        assert!(matches!(
+            find_native_executable(&dir.join("missing")),
+            Err(NativeImageError::ExecutableNotFound(_))
+        ));
+
+        fs::write(dir.join("app.jar"), "PK\x03\x04").unwrap();
+        fs::write(dir.join("run.sh"), "#!/usr/bin/env bash\n").unwrap();
+        set_executable(dir.join("run.sh")).unwrap();
+        fs::write(dir.join("libawt.so"), b"\x7fELF").unwrap();
+        set_executable(dir.join("libawt.so")).unwrap();
+        fs::write(dir.join("app"), b"\x7fELF").unwrap();
+
+        assert!(matches!(
+            find_native_executable(dir),
+            Err(NativeImageError::ExecutableNotFound(_))
+        ));
+
+        set_executable(dir.join("app")).unwrap();
+        assert_eq!(find_native_executable(dir).unwrap(), dir.join("app"));
+
+        fs::copy(dir.join("app"), dir.join("other")).unwrap();
+        assert!(matches!(
+            find_native_executable(dir),
+            Err(NativeImageError::MultipleExecutables(executables)) if executables == vec![dir.join("app"), dir.join("other")]
+        ));
+    }
+
+    #[test]
+    fn install_native_executable_copies_shared_libraries() {
+        let build_dir = tempfile::tempdir().unwrap();
+        let layer_dir = tempfile::tempdir().unwrap();
+
+        fs::write(build_dir.path().join("app"), b"\x7fELF").unwrap();
+        set_executable(build_dir.path().join("app")).unwrap();
+        fs::write(build_dir.path().join("libawt.so"), b"\x7fELF").unwrap();
+        fs::write(build_dir.path().join("app.jar"), "PK\x03\x04").unwrap();
+
+        let executable =
+            install_native_executable(&build_dir.path().join("app"), layer_dir.path()).unwrap();
+
+        assert_eq!(executable, layer_dir.path().join("app"));
+        assert!(is_executable_elf_file(&executable).unwrap());
+        assert!(layer_dir.path().join("libawt.so").is_file());
+        assert!(!layer_dir.path().join("app.jar").exists());
+    }
+}
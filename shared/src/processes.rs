@@ -0,0 +1,289 @@
+use crate::framework::LaunchTarget;
+use crate::result::none_on_not_found;
+use libcnb::data::launch::{Process, ProcessBuilder, ProcessType, ProcessTypeError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// An additional process type of the application, defined in the
+/// `[com.heroku.buildpacks.jvm.processes]` table of `project.toml`:
+///
+/// ```toml
+/// [com.heroku.buildpacks.jvm.processes.worker]
+/// args = ["--spring.batch.job.enabled=true"]
+/// java-opts = "-Xss512k"
+///
+/// [com.heroku.buildpacks.jvm.processes.release]
+/// command = "java $JAVA_OPTS -cp target/app.jar com.example.Migrate"
+/// ```
+///
+/// Without a `command`, the process launches the same artifact as the default `web` process.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProcessDefinition {
+    /// A shell command that replaces the command the process would otherwise run.
+    pub command: Option<String>,
+    /// Arguments that are passed to the application.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// JVM options that are used in addition to `JAVA_OPTS`.
+    pub java_opts: Option<String>,
+}
+
+/// Reads the additional process types from `project.toml` in the app directory. A missing
+/// `project.toml` file or table is not considered an error.
+pub fn read_process_definitions(
+    app_dir: &Path,
+) -> Result<BTreeMap<ProcessType, ProcessDefinition>, ProcessDefinitionsError> {
+    let Some(project_toml) =
+        none_on_not_found(std::fs::read_to_string(app_dir.join("project.toml")))
+            .map_err(ProcessDefinitionsError::Io)?
+    else {
+        return Ok(BTreeMap::new());
+    };
+
+    parse_process_definitions(&project_toml)
+}
+
+fn parse_process_definitions(
+    project_toml: &str,
+) -> Result<BTreeMap<ProcessType, ProcessDefinition>, ProcessDefinitionsError> {
+    let project_toml =
+        toml::from_str::<toml::Table>(project_toml).map_err(ProcessDefinitionsError::Parse)?;
+
+    let processes = ["com", "heroku", "buildpacks", "jvm", "processes"]
+        .iter()
+        .try_fold(&project_toml, |table, key| {
+            table.get(*key).and_then(toml::Value::as_table)
+        })
+        .cloned()
+        .unwrap_or_default();
+
+    processes
+        .into_iter()
+        .map(|(process_type, definition)| {
+            Ok((
+                process_type
+                    .parse::<ProcessType>()
+                    .map_err(ProcessDefinitionsError::InvalidProcessType)?,
+                definition
+                    .try_into::<ProcessDefinition>()
+                    .map_err(ProcessDefinitionsError::Parse)?,
+            ))
+        })
+        .collect()
+}
+
+/// Creates the processes for the given definitions. Definitions without a `command` launch the
+/// given target, which must therefore be available.
+pub fn additional_processes(
+    process_definitions: &BTreeMap<ProcessType, ProcessDefinition>,
+    launch_target: Option<&LaunchTarget>,
+) -> Result<Vec<Process>, ProcessDefinitionsError> {
+    process_definitions
+        .iter()
+        .map(|(process_type, definition)| {
+            let command = match (&definition.command, launch_target) {
+                (Some(command), _) => command.clone(),
+                (None, Some(launch_target)) => launch_command(launch_target, definition),
+                (None, None) => Err(ProcessDefinitionsError::MissingLaunchTarget(
+                    process_type.clone(),
+                ))?,
+            };
+
+            Ok(
+                ProcessBuilder::new(process_type.clone(), ["bash", "-c", &command])
+                    .default(process_type.as_str() == "web")
+                    .build(),
+            )
+        })
+        .collect()
+}
+
+fn launch_command(launch_target: &LaunchTarget, definition: &ProcessDefinition) -> String {
+    let java_opts = definition
+        .java_opts
+        .as_ref()
+        .map(|java_opts| format!("{java_opts} "))
+        .unwrap_or_default();
+
+    let args = if definition.args.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", shell_words::join(&definition.args))
+    };
+
+    match launch_target {
+        LaunchTarget::StartScript(start_script) => format!(
+            "JAVA_OPTS=\"{java_opts}$JAVA_OPTS\" {}{args}",
+            start_script.to_string_lossy()
+        ),
+        LaunchTarget::Jar(_) | LaunchTarget::Classpath { .. } => format!(
+            "java {java_opts}$JAVA_OPTS {}{args}",
+            launch_target.java_args()
+        ),
+        // Native executables accept runtime options such as -Xmx as arguments.
+        LaunchTarget::NativeExecutable(executable) => {
+            format!("{} {java_opts}", executable.to_string_lossy())
+                .trim_end()
+                .to_string()
+                + &args
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ProcessDefinitionsError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    InvalidProcessType(ProcessTypeError),
+    MissingLaunchTarget(ProcessType),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use libcnb::data::process_type;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_definitions() {
+        let process_definitions = parse_process_definitions(indoc! {r#"
+            [_]
+            schema-version = "0.2"
+
+            [com.heroku.buildpacks.jvm.processes.worker]
+            args = ["--spring.batch.job.name=import", "--input=my file.csv"]
+            java-opts = "-Xss512k"
+
+            [com.heroku.buildpacks.jvm.processes.release]
+            command = "java $JAVA_OPTS -cp target/app.jar com.example.Migrate"
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            process_definitions,
+            BTreeMap::from([
+                (
+                    process_type!("release"),
+                    ProcessDefinition {
+                        command: Some(String::from(
+                            "java $JAVA_OPTS -cp target/app.jar com.example.Migrate"
+                        )),
+                        ..ProcessDefinition::default()
+                    }
+                ),
+                (
+                    process_type!("worker"),
+                    ProcessDefinition {
+                        command: None,
+                        args: vec![
+                            String::from("--spring.batch.job.name=import"),
+                            String::from("--input=my file.csv")
+                        ],
+                        java_opts: Some(String::from("-Xss512k")),
+                    }
+                ),
+            ])
+        );
+
+        assert_eq!(
+            parse_process_definitions("[_]\nschema-version = \"0.2\"\n").unwrap(),
+            BTreeMap::new()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_definitions() {
+        assert!(matches!(
+            parse_process_definitions("[com.heroku.buildpacks.jvm.processes.worker]\nargz = []\n"),
+            Err(ProcessDefinitionsError::Parse(_))
+        ));
+
+        assert!(matches!(
+            parse_process_definitions(
+                "[com.heroku.buildpacks.jvm.processes.\"not valid\"]\nargs = []\n"
+            ),
+            Err(ProcessDefinitionsError::InvalidProcessType(_))
+        ));
+    }
+
+    #[test]
+    fn processes_for_launch_targets() {
+        let process_definitions = BTreeMap::from([(
+            process_type!("worker"),
+            ProcessDefinition {
+                command: None,
+                args: vec![String::from("--input=my file.csv")],
+                java_opts: Some(String::from("-Xss512k")),
+            },
+        )]);
+
+        assert_eq!(
+            additional_processes(
+                &process_definitions,
+                Some(&LaunchTarget::Jar(PathBuf::from("/workspace/target/app.jar")))
+            )
+            .unwrap(),
+            vec![
+                ProcessBuilder::new(
+                    process_type!("worker"),
+                    [
+                        "bash",
+                        "-c",
+                        "java -Xss512k $JAVA_OPTS -jar /workspace/target/app.jar '--input=my file.csv'"
+                    ]
+                )
+                .build()
+            ]
+        );
+
+        assert_eq!(
+            additional_processes(
+                &process_definitions,
+                Some(&LaunchTarget::StartScript(PathBuf::from(
+                    "/workspace/build/install/app/bin/app"
+                )))
+            )
+            .unwrap(),
+            vec![
+                ProcessBuilder::new(
+                    process_type!("worker"),
+                    [
+                        "bash",
+                        "-c",
+                        "JAVA_OPTS=\"-Xss512k $JAVA_OPTS\" /workspace/build/install/app/bin/app '--input=my file.csv'"
+                    ]
+                )
+                .build()
+            ]
+        );
+
+        assert_eq!(
+            additional_processes(
+                &process_definitions,
+                Some(&LaunchTarget::NativeExecutable(PathBuf::from(
+                    "/layers/native-image/app"
+                )))
+            )
+            .unwrap(),
+            vec![
+                ProcessBuilder::new(
+                    process_type!("worker"),
+                    [
+                        "bash",
+                        "-c",
+                        "/layers/native-image/app -Xss512k '--input=my file.csv'"
+                    ]
+                )
+                .build()
+            ]
+        );
+
+        assert!(matches!(
+            additional_processes(&process_definitions, None),
+            Err(ProcessDefinitionsError::MissingLaunchTarget(process_type)) if process_type == process_type!("worker")
+        ));
+    }
+}
@@ -0,0 +1,524 @@
+use crate::result::none_on_not_found;
+use crate::system_properties::{ReadSystemPropertiesError, read_system_properties};
+use libcnb::Env;
+use libcnb::data::build_plan::Require;
+use libcnb::data::buildpack_plan::Entry;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+pub const JAVA_RUNTIME_IMAGE_ENV_VAR_NAME: &str = "JAVA_RUNTIME_IMAGE";
+pub const JAVA_RUNTIME_JLINK_MODULES_ENV_VAR_NAME: &str = "JAVA_RUNTIME_JLINK_MODULES";
+const JAVA_RUNTIME_IMAGE_PROPERTY_NAME: &str = "java.runtime.image";
+const JAVA_RUNTIME_JLINK_MODULES_PROPERTY_NAME: &str = "java.runtime.jlink.modules";
+/// Key of the `jdk` build plan requirement metadata that lists the runtime image types the
+/// requiring buildpack creates after the application has been built.
+const JDK_REQUIREMENT_RUNTIME_IMAGES_METADATA_KEY: &str = "runtime_images";
+
+/// The kind of Java runtime that is installed in the launch image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum RuntimeImageType {
+    /// The full JDK is available at launch. This is the default.
+    #[default]
+    Jdk,
+    /// Only a JRE, derived from the installed JDK, is available at launch.
+    Jre,
+    /// A runtime that only contains the modules required by the application is available at
+    /// launch. This runtime is created after the application has been built.
+    Jlink,
+    /// The application is compiled to a native executable with `native-image`. No Java
+    /// runtime is available at launch.
+    Native,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct RuntimeImageConfig {
+    pub image_type: RuntimeImageType,
+    /// Modules that are added to a jlink runtime in addition to the ones detected by jdeps.
+    pub additional_modules: Vec<String>,
+}
+
+/// Reads the runtime image configuration from `system.properties` (`java.runtime.image` and
+/// `java.runtime.jlink.modules`) or the corresponding environment variables. The environment
+/// variables take precedence.
+pub fn read_runtime_image_config(
+    app_dir: &Path,
+    env: &Env,
+) -> Result<RuntimeImageConfig, RuntimeImageConfigError> {
+    let properties =
+        read_system_properties(app_dir).map_err(RuntimeImageConfigError::ReadSystemProperties)?;
+
+    let image_type = match env
+        .get_string_lossy(JAVA_RUNTIME_IMAGE_ENV_VAR_NAME)
+        .or_else(|| properties.get(JAVA_RUNTIME_IMAGE_PROPERTY_NAME).cloned())
+        .map(|value| value.trim().to_lowercase())
+        .as_deref()
+    {
+        None | Some("" | "jdk") => RuntimeImageType::Jdk,
+        Some("jre") => RuntimeImageType::Jre,
+        Some("jlink") => RuntimeImageType::Jlink,
+        Some("native") => RuntimeImageType::Native,
+        Some(other) => {
+            return Err(RuntimeImageConfigError::UnknownImageType(String::from(
+                other,
+            )));
+        }
+    };
+
+    let additional_modules = env
+        .get_string_lossy(JAVA_RUNTIME_JLINK_MODULES_ENV_VAR_NAME)
+        .or_else(|| {
+            properties
+                .get(JAVA_RUNTIME_JLINK_MODULES_PROPERTY_NAME)
+                .cloned()
+        })
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|module| !module.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(RuntimeImageConfig {
+        image_type,
+        additional_modules,
+    })
+}
+
+/// The `jdk` build plan requirement for build tool buildpacks that create jlink runtimes and
+/// native executables once the application has been built.
+#[must_use]
+pub fn jdk_requirement() -> Require {
+    let mut require = Require::new("jdk");
+
+    require.metadata.insert(
+        String::from(JDK_REQUIREMENT_RUNTIME_IMAGES_METADATA_KEY),
+        toml::Value::Array(
+            [RuntimeImageType::Jlink, RuntimeImageType::Native]
+                .iter()
+                .map(|image_type| toml::Value::from(image_type.name()))
+                .collect(),
+        ),
+    );
+
+    require
+}
+
+/// Determines if the given runtime image type can be provided, based on the `jdk` entries of the
+/// buildpack plan. JDK and JRE runtimes can always be provided, jlink runtimes and native
+/// executables require a buildpack that creates them after the application has been built.
+#[must_use]
+pub fn is_runtime_image_type_provided(
+    buildpack_plan_entries: &[Entry],
+    image_type: RuntimeImageType,
+) -> bool {
+    match image_type {
+        RuntimeImageType::Jdk | RuntimeImageType::Jre => true,
+        RuntimeImageType::Jlink | RuntimeImageType::Native => buildpack_plan_entries
+            .iter()
+            .filter(|entry| entry.name == "jdk")
+            .filter_map(|entry| {
+                entry
+                    .metadata
+                    .get(JDK_REQUIREMENT_RUNTIME_IMAGES_METADATA_KEY)
+            })
+            .filter_map(toml::Value::as_array)
+            .flatten()
+            .any(|value| value.as_str() == Some(image_type.name())),
+    }
+}
+
+impl RuntimeImageType {
+    /// The name of the runtime image type, as used in the configuration.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeImageType::Jdk => "jdk",
+            RuntimeImageType::Jre => "jre",
+            RuntimeImageType::Jlink => "jlink",
+            RuntimeImageType::Native => "native",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RuntimeImageConfigError {
+    ReadSystemProperties(ReadSystemPropertiesError),
+    UnknownImageType(String),
+}
+
+/// Creates a JRE from the JDK at `java_home` in the (empty) `destination` directory.
+///
+/// For OpenJDK 8, the JRE that is bundled with the JDK is copied. For later versions, the JRE is
+/// created with `jlink` and contains all modules of the JDK, except development tools.
+pub fn create_jre_image(java_home: &Path, destination: &Path) -> Result<(), RuntimeImageError> {
+    let bundled_jre_path = java_home.join("jre");
+
+    if bundled_jre_path.is_dir() {
+        // `cp -a` is used to keep symlinks, most importantly the one for the CA keystore.
+        let mut command = Command::new("cp");
+        command
+            .arg("-a")
+            .arg(bundled_jre_path.join("."))
+            .arg(destination);
+
+        run(command).map(|_| ())
+    } else {
+        let modules = list_modules(java_home)?
+            .into_iter()
+            .filter(|module| !JDK_DEVELOPMENT_MODULES.contains(&module.as_str()))
+            .collect();
+
+        jlink(java_home, &modules, destination)
+    }
+}
+
+/// Creates a runtime with `jlink` that only contains the modules `jdeps` finds in the given
+/// application JAR files. The modules that were linked are returned.
+pub fn create_jlink_image(
+    java_home: &Path,
+    application_jars: &[PathBuf],
+    additional_modules: &[String],
+    destination: &Path,
+) -> Result<BTreeSet<String>, RuntimeImageError> {
+    if !java_home.join("bin").join("jlink").exists() {
+        return Err(RuntimeImageError::JlinkNotAvailable);
+    }
+
+    let available_modules = list_modules(java_home)?;
+
+    let nested_jars_dir = tempfile::tempdir().map_err(RuntimeImageError::Io)?;
+    let mut jars = application_jars.to_vec();
+    for application_jar in application_jars {
+        jars.extend(extract_nested_jars(
+            java_home,
+            application_jar,
+            nested_jars_dir.path(),
+        )?);
+    }
+
+    let mut modules = DEFAULT_JLINK_MODULES
+        .iter()
+        .map(|module| String::from(*module))
+        .filter(|module| available_modules.contains(module))
+        .collect::<BTreeSet<_>>();
+
+    modules.extend(additional_modules.iter().cloned());
+
+    if !jars.is_empty() {
+        modules.extend(jdeps_modules(java_home, &jars)?);
+    }
+
+    jlink(java_home, &modules, destination)?;
+    Ok(modules)
+}
+
+/// Recursively finds all JAR files in the given directory, such as the output directory of a
+/// build tool. JAR files with sources or documentation are ignored.
+pub fn find_application_jars(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut jars = Vec::new();
+
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                jars.extend(find_application_jars(&path)?);
+            } else if path.extension().is_some_and(|extension| extension == "jar")
+                && !path.file_stem().is_some_and(|file_stem| {
+                    let file_stem = file_stem.to_string_lossy();
+                    file_stem.ends_with("-sources") || file_stem.ends_with("-javadoc")
+                })
+            {
+                jars.push(path);
+            }
+        }
+    }
+
+    jars.sort();
+    Ok(jars)
+}
+
+#[derive(Debug)]
+pub enum RuntimeImageError {
+    Io(std::io::Error),
+    JlinkNotAvailable,
+    CommandFailed(String, Output),
+}
+
+fn list_modules(java_home: &Path) -> Result<BTreeSet<String>, RuntimeImageError> {
+    let mut command = Command::new(java_home.join("bin").join("java"));
+    command.arg("--list-modules");
+
+    // Each line has the format `java.base@21.0.5`.
+    run(command).map(|output| {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split('@').next())
+            .map(str::trim)
+            .filter(|module| !module.is_empty())
+            .map(String::from)
+            .collect()
+    })
+}
+
+fn jdeps_modules(
+    java_home: &Path,
+    jars: &[PathBuf],
+) -> Result<BTreeSet<String>, RuntimeImageError> {
+    let mut command = Command::new(java_home.join("bin").join("jdeps"));
+    command
+        .args([
+            "--ignore-missing-deps",
+            "--print-module-deps",
+            "--multi-release",
+            "base",
+            "-q",
+        ])
+        .args(jars);
+
+    run(command).map(|output| parse_module_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Extracts JAR files that are nested in a Spring Boot or WAR archive into a subdirectory of
+/// `destination` and returns their paths.
+fn extract_nested_jars(
+    java_home: &Path,
+    jar: &Path,
+    destination: &Path,
+) -> Result<Vec<PathBuf>, RuntimeImageError> {
+    let jar_destination = destination.join(
+        jar.file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    );
+
+    std::fs::create_dir_all(&jar_destination).map_err(RuntimeImageError::Io)?;
+
+    let mut command = Command::new(java_home.join("bin").join("jar"));
+    command
+        .current_dir(&jar_destination)
+        .arg("--extract")
+        .arg("--file")
+        .arg(jar)
+        .args(NESTED_JAR_DIRECTORIES);
+
+    run(command)?;
+
+    find_application_jars(&jar_destination).map_err(RuntimeImageError::Io)
+}
+
+fn jlink(
+    java_home: &Path,
+    modules: &BTreeSet<String>,
+    destination: &Path,
+) -> Result<(), RuntimeImageError> {
+    // jlink requires that the output directory does not exist yet.
+    none_on_not_found(std::fs::remove_dir_all(destination)).map_err(RuntimeImageError::Io)?;
+
+    let mut command = Command::new(java_home.join("bin").join("jlink"));
+    command
+        .args([
+            "--no-header-files",
+            "--no-man-pages",
+            "--strip-debug",
+            "--add-modules",
+        ])
+        .arg(modules.iter().cloned().collect::<Vec<_>>().join(","))
+        .arg("--output")
+        .arg(destination);
+
+    run(command)?;
+
+    // jlink copies the CA keystore. When the JDK uses a symlink to the keystore of the base image,
+    // the same symlink is used in the runtime so that it stays up-to-date when the image is rebased.
+    let relative_cacerts_path = Path::new("lib/security/cacerts");
+    if let Ok(cacerts_link_target) = std::fs::read_link(java_home.join(relative_cacerts_path)) {
+        let cacerts_path = destination.join(relative_cacerts_path);
+
+        std::fs::remove_file(&cacerts_path)
+            .and_then(|()| std::os::unix::fs::symlink(cacerts_link_target, cacerts_path))
+            .map_err(RuntimeImageError::Io)?;
+    }
+
+    Ok(())
+}
+
+fn run(mut command: Command) -> Result<Output, RuntimeImageError> {
+    let output = command.output().map_err(RuntimeImageError::Io)?;
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(RuntimeImageError::CommandFailed(
+            format!("{command:?}"),
+            output,
+        ))
+    }
+}
+
+fn parse_module_list(value: &str) -> BTreeSet<String> {
+    value
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+const NESTED_JAR_DIRECTORIES: [&str; 3] = ["BOOT-INF/lib", "WEB-INF/lib", "WEB-INF/lib-provided"];
+
+// Modules that are commonly loaded without a static reference, i.e. via the service loader.
+const DEFAULT_JLINK_MODULES: [&str; 3] = ["java.base", "jdk.crypto.ec", "jdk.charsets"];
+
+const JDK_DEVELOPMENT_MODULES: [&str; 16] = [
+    "jdk.compiler",
+    "jdk.editpad",
+    "jdk.hotspot.agent",
+    "jdk.internal.ed",
+    "jdk.internal.le",
+    "jdk.internal.opt",
+    "jdk.jartool",
+    "jdk.javadoc",
+    "jdk.jcmd",
+    "jdk.jconsole",
+    "jdk.jdeps",
+    "jdk.jdi",
+    "jdk.jlink",
+    "jdk.jpackage",
+    "jdk.jshell",
+    "jdk.jstatd",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_image_type_providers() {
+        let entries_from_requires = |requires: Vec<Require>| {
+            requires
+                .into_iter()
+                .map(|require| Entry {
+                    name: require.name,
+                    metadata: require.metadata,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let without_provider = entries_from_requires(vec![Require::new("jdk")]);
+        let with_provider = entries_from_requires(vec![Require::new("jdk"), jdk_requirement()]);
+
+        for (entries, image_type, expected) in [
+            (&without_provider, RuntimeImageType::Jdk, true),
+            (&without_provider, RuntimeImageType::Jre, true),
+            (&without_provider, RuntimeImageType::Jlink, false),
+            (&without_provider, RuntimeImageType::Native, false),
+            (&with_provider, RuntimeImageType::Jlink, true),
+            (&with_provider, RuntimeImageType::Native, true),
+        ] {
+            assert_eq!(
+                is_runtime_image_type_provided(entries, image_type),
+                expected,
+                "{image_type:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn read_runtime_image_config_defaults() {
+        let app_dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            read_runtime_image_config(app_dir.path(), &Env::new()).unwrap(),
+            RuntimeImageConfig::default()
+        );
+    }
+
+    #[test]
+    fn read_runtime_image_config_from_system_properties_and_env() {
+        let app_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            app_dir.path().join("system.properties"),
+            "java.runtime.image=JLink\njava.runtime.jlink.modules=java.sql, jdk.localedata,\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_runtime_image_config(app_dir.path(), &Env::new()).unwrap(),
+            RuntimeImageConfig {
+                image_type: RuntimeImageType::Jlink,
+                additional_modules: vec![String::from("java.sql"), String::from("jdk.localedata")],
+            }
+        );
+
+        let mut env = Env::new();
+        env.insert(JAVA_RUNTIME_IMAGE_ENV_VAR_NAME, "jre");
+
+        assert_eq!(
+            read_runtime_image_config(app_dir.path(), &env)
+                .unwrap()
+                .image_type,
+            RuntimeImageType::Jre
+        );
+
+        env.insert(JAVA_RUNTIME_IMAGE_ENV_VAR_NAME, "native");
+
+        assert_eq!(
+            read_runtime_image_config(app_dir.path(), &env)
+                .unwrap()
+                .image_type,
+            RuntimeImageType::Native
+        );
+
+        env.insert(JAVA_RUNTIME_IMAGE_ENV_VAR_NAME, "graal");
+
+        assert!(matches!(
+            read_runtime_image_config(app_dir.path(), &env),
+            Err(RuntimeImageConfigError::UnknownImageType(value)) if value == "graal"
+        ));
+    }
+
+    #[test]
+    fn find_application_jars_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("quarkus-app/lib/main")).unwrap();
+        for file in [
+            "app.jar",
+            "app-sources.jar",
+            "app-javadoc.jar",
+            "classes.txt",
+            "quarkus-app/lib/main/dependency.jar",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        assert_eq!(
+            find_application_jars(dir.path()).unwrap(),
+            vec![
+                dir.path().join("app.jar"),
+                dir.path().join("quarkus-app/lib/main/dependency.jar")
+            ]
+        );
+
+        assert_eq!(
+            find_application_jars(&dir.path().join("missing")).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn parse_jdeps_module_list() {
+        assert_eq!(
+            parse_module_list("java.base,java.logging,java.sql\n"),
+            BTreeSet::from([
+                String::from("java.base"),
+                String::from("java.logging"),
+                String::from("java.sql")
+            ])
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use libcnb::data::sbom::SbomFormat;
+use libcnb::sbom::Sbom;
+use serde_json::json;
+
+/// A resolved Maven artifact that is bundled with an application.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct MavenArtifact {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+    pub artifact_type: String,
+    pub classifier: Option<String>,
+}
+
+impl MavenArtifact {
+    /// The package URL of the artifact, see: <https://github.com/package-url/purl-spec>
+    #[must_use]
+    pub fn purl(&self) -> String {
+        let mut qualifiers = url::form_urlencoded::Serializer::new(String::new());
+
+        if let Some(classifier) = &self.classifier {
+            qualifiers.append_pair("classifier", classifier);
+        }
+
+        if self.artifact_type != "jar" {
+            qualifiers.append_pair("type", &self.artifact_type);
+        }
+
+        let qualifiers = qualifiers.finish();
+
+        format!(
+            "pkg:maven/{}/{}@{}{}",
+            encode_purl_segment(&self.group_id),
+            encode_purl_segment(&self.artifact_id),
+            encode_purl_segment(&self.version),
+            if qualifiers.is_empty() {
+                String::new()
+            } else {
+                format!("?{qualifiers}")
+            }
+        )
+    }
+}
+
+/// Creates a `CycloneDX` SBOM that lists the given Maven artifacts as libraries.
+#[must_use]
+pub fn maven_artifacts_cyclonedx_sbom(artifacts: &[MavenArtifact]) -> Sbom {
+    let mut artifacts = artifacts.to_vec();
+    artifacts.sort();
+    artifacts.dedup();
+
+    let components = artifacts
+        .iter()
+        .map(|artifact| {
+            json!({
+                "type": "library",
+                "bom-ref": artifact.purl(),
+                "group": artifact.group_id,
+                "name": artifact.artifact_id,
+                "version": artifact.version,
+                "purl": artifact.purl(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Sbom::from_bytes(
+        SbomFormat::CycloneDxJson,
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.4",
+            "version": 1,
+            "components": components,
+        })
+        .to_string(),
+    )
+}
+
+fn encode_purl_segment(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(group_id: &str, artifact_id: &str, version: &str) -> MavenArtifact {
+        MavenArtifact {
+            group_id: String::from(group_id),
+            artifact_id: String::from(artifact_id),
+            version: String::from(version),
+            artifact_type: String::from("jar"),
+            classifier: None,
+        }
+    }
+
+    #[test]
+    fn purl() {
+        assert_eq!(
+            artifact("io.undertow", "undertow-core", "2.3.21.Final").purl(),
+            "pkg:maven/io.undertow/undertow-core@2.3.21.Final"
+        );
+
+        assert_eq!(
+            MavenArtifact {
+                artifact_type: String::from("test-jar"),
+                classifier: Some(String::from("linux-x86_64")),
+                ..artifact("io.netty", "netty-transport-native-epoll", "4.1.100.Final")
+            }
+            .purl(),
+            "pkg:maven/io.netty/netty-transport-native-epoll@4.1.100.Final?classifier=linux-x86_64&type=test-jar"
+        );
+    }
+
+    #[test]
+    fn cyclonedx_sbom() {
+        let sbom = maven_artifacts_cyclonedx_sbom(&[
+            artifact("org.jboss.logging", "jboss-logging", "3.4.3.Final"),
+            artifact("com.google.guava", "guava", "32.0.0-jre"),
+            artifact("org.jboss.logging", "jboss-logging", "3.4.3.Final"),
+        ]);
+
+        assert_eq!(sbom.format, SbomFormat::CycloneDxJson);
+
+        let document = serde_json::from_slice::<serde_json::Value>(&sbom.data).unwrap();
+        assert_eq!(document["bomFormat"], "CycloneDX");
+        assert_eq!(document["components"].as_array().unwrap().len(), 2);
+        assert_eq!(document["components"][0]["group"], "com.google.guava");
+        assert_eq!(
+            document["components"][1]["purl"],
+            "pkg:maven/org.jboss.logging/jboss-logging@3.4.3.Final"
+        );
+    }
+}
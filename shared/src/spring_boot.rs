@@ -0,0 +1,290 @@
+use crate::fs::list_directory_contents;
+use crate::jar::read_manifest;
+use crate::system_properties::{ReadBooleanSettingError, read_boolean_setting};
+use libcnb::Env;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const SPRING_BOOT_EXTRACT_LAYERS_ENV_VAR_NAME: &str = "SPRING_BOOT_EXTRACT_LAYERS";
+const SPRING_BOOT_EXTRACT_LAYERS_PROPERTY_NAME: &str = "spring-boot.extract-layers";
+
+/// Reads whether Spring Boot JAR files should be extracted into separate layers from
+/// `system.properties` (`spring-boot.extract-layers`) or the `SPRING_BOOT_EXTRACT_LAYERS`
+/// environment variable. The environment variable takes precedence. Extraction is disabled by
+/// default.
+pub fn read_extract_layers_config(
+    app_dir: &Path,
+    env: &Env,
+) -> Result<bool, ReadBooleanSettingError> {
+    read_boolean_setting(
+        app_dir,
+        env,
+        SPRING_BOOT_EXTRACT_LAYERS_ENV_VAR_NAME,
+        SPRING_BOOT_EXTRACT_LAYERS_PROPERTY_NAME,
+    )
+    .map(Option::unwrap_or_default)
+}
+
+/// Returns the class that Spring Boot's launcher starts, if the given JAR file is a Spring Boot
+/// JAR file that was built with a layers index.
+pub fn layered_jar_start_class(jar_path: &Path) -> std::io::Result<Option<String>> {
+    read_manifest(jar_path).map(|mut attributes| {
+        attributes
+            .remove(LAYERS_INDEX_MANIFEST_ATTRIBUTE)
+            .and(attributes.remove(START_CLASS_MANIFEST_ATTRIBUTE))
+            .filter(|start_class| !start_class.is_empty())
+    })
+}
+
+/// A layer of a Spring Boot JAR file that was extracted with `-Djarmode=tools`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExtractedLayer {
+    /// The name of the layer as defined in the layers index, i.e. `dependencies`.
+    pub name: String,
+    pub path: PathBuf,
+    /// A SHA-256 digest of the paths and contents of all files in the layer.
+    pub digest: String,
+}
+
+/// Extracts the layers of the given Spring Boot JAR file into the (empty) `destination` directory
+/// with `java -Djarmode=tools -jar <jar> extract --layers`. Layers without any files are omitted.
+pub fn extract_layers(
+    env: &Env,
+    jar_path: &Path,
+    destination: &Path,
+) -> Result<Vec<ExtractedLayer>, SpringBootLayersError> {
+    let mut command = Command::new("java");
+    command
+        .envs(env)
+        .arg("-Djarmode=tools")
+        .arg("-jar")
+        .arg(jar_path)
+        .args(["extract", "--layers", "--destination"])
+        .arg(destination);
+
+    let output = command.output().map_err(SpringBootLayersError::Io)?;
+    if !output.status.success() {
+        return Err(SpringBootLayersError::CommandFailed(
+            format!("{command:?}"),
+            output,
+        ));
+    }
+
+    let mut layer_paths = list_directory_contents(destination)
+        .map_err(SpringBootLayersError::Io)?
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+
+    layer_paths.sort();
+
+    let mut layers = Vec::new();
+    for layer_path in layer_paths {
+        let mut files = Vec::new();
+        collect_files(&layer_path, &mut files).map_err(SpringBootLayersError::Io)?;
+
+        if files.is_empty() {
+            continue;
+        }
+
+        layers.push(ExtractedLayer {
+            name: layer_path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            digest: digest(&layer_path, &files).map_err(SpringBootLayersError::Io)?,
+            path: layer_path,
+        });
+    }
+
+    Ok(layers)
+}
+
+/// Copies the contents of an extracted layer into the given (empty) layer directory.
+pub fn install_layer(
+    layer: &ExtractedLayer,
+    destination: &Path,
+) -> Result<(), SpringBootLayersError> {
+    // `cp -a` is used to keep file modes and timestamps of the extracted files.
+    let mut command = Command::new("cp");
+    command.arg("-a").arg(layer.path.join(".")).arg(destination);
+
+    let output = command.output().map_err(SpringBootLayersError::Io)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SpringBootLayersError::CommandFailed(
+            format!("{command:?}"),
+            output,
+        ))
+    }
+}
+
+/// Builds the classpath for the installed layers, given as pairs of layer name and directory.
+///
+/// The extracted application JAR file is put in the root of the `application` layer, libraries
+/// are put in a `lib` directory in the other layers. Both are added with classpath wildcards, the
+/// application layer first.
+#[must_use]
+pub fn layers_classpath(layer_dirs: &[(String, PathBuf)]) -> Vec<String> {
+    let mut layer_dirs = layer_dirs.to_vec();
+    layer_dirs.sort_by_key(|(name, _)| name != APPLICATION_LAYER_NAME);
+
+    layer_dirs
+        .iter()
+        .flat_map(|(_, layer_dir)| {
+            [
+                format!("{}/*", layer_dir.to_string_lossy()),
+                format!("{}/lib/*", layer_dir.to_string_lossy()),
+            ]
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum SpringBootLayersError {
+    Io(std::io::Error),
+    CommandFailed(String, Output),
+    InvalidLayerName(String),
+}
+
+fn collect_files(dir: &Path, acc: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for path in list_directory_contents(dir)? {
+        if path.is_dir() {
+            collect_files(&path, acc)?;
+        } else {
+            acc.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn digest(layer_path: &Path, files: &[PathBuf]) -> std::io::Result<String> {
+    let mut files = files.to_vec();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative_path = file.strip_prefix(layer_path).unwrap_or(&file);
+        hasher.update(relative_path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(std::fs::read(&file)?);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut acc, byte| {
+            // Writing to a String cannot fail.
+            let _ = write!(acc, "{byte:02x}");
+            acc
+        }))
+}
+
+const APPLICATION_LAYER_NAME: &str = "application";
+const LAYERS_INDEX_MANIFEST_ATTRIBUTE: &str = "Spring-Boot-Layers-Index";
+const START_CLASS_MANIFEST_ATTRIBUTE: &str = "Start-Class";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn extract_layers_is_disabled_by_default() {
+        let app_dir = tempfile::tempdir().unwrap();
+        assert!(!read_extract_layers_config(app_dir.path(), &Env::new()).unwrap());
+
+        fs::write(
+            app_dir.path().join("system.properties"),
+            "spring-boot.extract-layers=true\n",
+        )
+        .unwrap();
+        assert!(read_extract_layers_config(app_dir.path(), &Env::new()).unwrap());
+    }
+
+    #[test]
+    fn start_class_of_layered_jars() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        for (file_name, manifest) in [
+            (
+                "layered.jar",
+                "Main-Class: org.springframework.boot.loader.launch.JarLauncher\nStart-Class: com.example.App\nSpring-Boot-Layers-Index: BOOT-INF/layers.idx\n",
+            ),
+            (
+                "unlayered.jar",
+                "Main-Class: org.springframework.boot.loader.launch.JarLauncher\nStart-Class: com.example.App\n",
+            ),
+        ] {
+            let mut writer =
+                zip::ZipWriter::new(fs::File::create(temp_dir.path().join(file_name)).unwrap());
+            writer
+                .start_file(
+                    "META-INF/MANIFEST.MF",
+                    zip::write::SimpleFileOptions::default(),
+                )
+                .unwrap();
+            writer.write_all(manifest.as_bytes()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        assert_eq!(
+            layered_jar_start_class(&temp_dir.path().join("layered.jar"))
+                .unwrap()
+                .as_deref(),
+            Some("com.example.App")
+        );
+        assert_eq!(
+            layered_jar_start_class(&temp_dir.path().join("unlayered.jar")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn digest_depends_on_paths_and_contents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layer_path = temp_dir.path().join("dependencies");
+        fs::create_dir_all(layer_path.join("lib")).unwrap();
+        fs::write(layer_path.join("lib/a.jar"), "a").unwrap();
+
+        let files = vec![layer_path.join("lib/a.jar")];
+        let initial_digest = digest(&layer_path, &files).unwrap();
+        assert_eq!(digest(&layer_path, &files).unwrap(), initial_digest);
+
+        fs::write(layer_path.join("lib/a.jar"), "b").unwrap();
+        assert_ne!(digest(&layer_path, &files).unwrap(), initial_digest);
+
+        fs::rename(layer_path.join("lib/a.jar"), layer_path.join("lib/c.jar")).unwrap();
+        fs::write(layer_path.join("lib/c.jar"), "a").unwrap();
+        assert_ne!(
+            digest(&layer_path, &[layer_path.join("lib/c.jar")]).unwrap(),
+            initial_digest
+        );
+    }
+
+    #[test]
+    fn classpath_starts_with_application_layer() {
+        assert_eq!(
+            layers_classpath(&[
+                (
+                    String::from("dependencies"),
+                    PathBuf::from("/layers/maven/spring-boot-dependencies")
+                ),
+                (
+                    String::from("application"),
+                    PathBuf::from("/layers/maven/spring-boot-application")
+                ),
+            ]),
+            vec![
+                "/layers/maven/spring-boot-application/*",
+                "/layers/maven/spring-boot-application/lib/*",
+                "/layers/maven/spring-boot-dependencies/*",
+                "/layers/maven/spring-boot-dependencies/lib/*",
+            ]
+        );
+    }
+}
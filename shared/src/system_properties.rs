@@ -1,4 +1,5 @@
 use crate::result::none_on_not_found;
+use libcnb::Env;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -36,6 +37,44 @@ pub fn write_system_properties(
         })
 }
 
+/// Reads a boolean setting from the given environment variable or, if it is not set, the given
+/// property in `system.properties`. Unset settings are `None`, valid values are `true` and `false`.
+pub fn read_boolean_setting(
+    app_dir: &Path,
+    env: &Env,
+    env_var_name: &str,
+    property_name: &str,
+) -> Result<Option<bool>, ReadBooleanSettingError> {
+    let (name, value) = match env.get_string_lossy(env_var_name) {
+        Some(value) => (env_var_name, Some(value)),
+        None => (
+            property_name,
+            read_system_properties(app_dir)
+                .map_err(ReadBooleanSettingError::ReadSystemProperties)?
+                .get(property_name)
+                .cloned(),
+        ),
+    };
+
+    value
+        .map(|value| {
+            value
+                .trim()
+                .parse::<bool>()
+                .map_err(|_| ReadBooleanSettingError::InvalidValue {
+                    name: String::from(name),
+                    value,
+                })
+        })
+        .transpose()
+}
+
+#[derive(Debug)]
+pub enum ReadBooleanSettingError {
+    ReadSystemProperties(ReadSystemPropertiesError),
+    InvalidValue { name: String, value: String },
+}
+
 #[derive(Debug)]
 pub enum ReadSystemPropertiesError {
     IoError(std::io::Error),
@@ -49,3 +88,29 @@ pub enum WriteSystemPropertiesError {
 }
 
 const SYSTEM_PROPERTIES_FILE_NAME: &str = "system.properties";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_boolean_setting_from_env_and_system_properties() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let read = |env: &Env| read_boolean_setting(app_dir.path(), env, "FEATURE", "feature");
+
+        assert_eq!(read(&Env::new()).unwrap(), None);
+
+        fs::write(app_dir.path().join("system.properties"), "feature=true\n").unwrap();
+        assert_eq!(read(&Env::new()).unwrap(), Some(true));
+
+        let mut env = Env::new();
+        env.insert("FEATURE", "false");
+        assert_eq!(read(&env).unwrap(), Some(false));
+
+        env.insert("FEATURE", "yes");
+        assert!(matches!(
+            read(&env),
+            Err(ReadBooleanSettingError::InvalidValue { name, value }) if name == "FEATURE" && value == "yes"
+        ));
+    }
+}